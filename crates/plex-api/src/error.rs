@@ -1,4 +1,4 @@
-use crate::media_container::server::Feature;
+use crate::media_container::{player::ProtocolCapability, server::Feature};
 use isahc::{AsyncBody, AsyncReadResponseExt, Response as HttpResponse};
 use serde::Deserialize;
 use thiserror::Error;
@@ -59,6 +59,21 @@ pub enum Error {
     DeviceConnectionNotSupported,
     #[error("Device doesn't have any exposed connection endpoints.")]
     DeviceConnectionsIsEmpty,
+    #[error("The device wasn't retrieved from the devices list.")]
+    DeviceIdNotAvailable,
+    #[error("Refusing to remove the device the client identifies as.")]
+    CurrentDeviceRemovalRefused,
+    #[error("A device cleanup needs a last seen date or products to match.")]
+    DeviceCleanupWithoutConditions,
+    #[error("Failed to remove some of the devices: {failed:?}.")]
+    DeviceCleanupIncomplete {
+        /// The identifiers of the devices that were removed.
+        removed: Vec<String>,
+        /// The identifiers of the devices that weren't, with the reason.
+        failed: Vec<(String, Self)>,
+    },
+    #[error("The player doesn't support the `{0}` capability.")]
+    PlayerCapabilityNotSupported(ProtocolCapability),
     #[error("Requested unknown setting: {0}.")]
     RequestedSettingNotFound(String),
     #[error("You can't set setting to a value of a different type.")]
@@ -75,6 +90,30 @@ pub enum Error {
     TranscodeError(String),
     #[error("The server thinks the client should just play the original media.")]
     TranscodeRefused,
+    #[error("The server didn't report an id for the playback session.")]
+    SessionIdNotAvailable,
+    #[error("The server didn't report an id for the media part.")]
+    PartIdNotAvailable,
+    #[error("At least one item is required.")]
+    NoItemsProvided,
+    #[error("The item wasn't retrieved from a playlist.")]
+    PlaylistItemIdNotAvailable,
+    #[error("The item wasn't retrieved from a play queue.")]
+    PlayQueueItemIdNotAvailable,
+    #[error("The item can't be played from a play queue.")]
+    PlayQueueItemNotSupported,
+    #[error("The server doesn't advertise the Match feature.")]
+    MatchNotSupported,
+    #[error("The library doesn't support filtering by `{0}`.")]
+    SearchFilterNotSupported(String),
+    #[error("The library doesn't support sorting by `{0}`.")]
+    SearchSortNotSupported(String),
+    #[error("The server doesn't support the agent `{0}` for this type of library.")]
+    LibraryAgentNotSupported(String),
+    #[error("The server doesn't support the scanner `{0}` for this type of library.")]
+    LibraryScannerNotSupported(String),
+    #[error("Value `{value}` is not valid for the filter `{filter}`.")]
+    SearchFilterValueNotFound { filter: String, value: String },
     #[error("Only invites with status pending_received can be accepted.")]
     InviteAcceptingNotPendingReceived,
     #[error("Unexpected error. Please create a bug report.")]
@@ -0,0 +1,447 @@
+//! GDM (“G'Day Mate”) is the UDP based protocol Plex apps use to find servers
+//! and players on the local network without going through plex.tv.
+//!
+//! A search is a `M-SEARCH` datagram sent to a well-known port, every device
+//! listening on it replies with a few HTTP-like headers describing itself.
+
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use futures::channel::oneshot;
+use tracing::{debug, trace, warn};
+
+use crate::{
+    device::DeviceConnection,
+    media_container::player::{DeviceClass, Player as PlayerResources, ProtocolCapability},
+    HttpClient, Player, Result, Server,
+};
+
+/// The multicast group GDM searches are sent to.
+pub const MULTICAST_ADDR: Ipv4Addr = Ipv4Addr::new(239, 0, 0, 250);
+/// The port players listen on for searches.
+pub const PLAYER_SEARCH_PORT: u16 = 32412;
+/// The port servers listen on for searches.
+pub const SERVER_SEARCH_PORT: u16 = 32414;
+
+const SEARCH_REQUEST: &[u8] = b"M-SEARCH * HTTP/1.1\r\n\r\n";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceType {
+    Server,
+    Player,
+    Unknown,
+}
+
+/// A device that answered a GDM search.
+#[derive(Debug, Clone)]
+pub struct DiscoveredDevice {
+    /// The address the answer came from.
+    pub address: IpAddr,
+    pub device_type: DeviceType,
+    pub resource_identifier: String,
+    pub name: String,
+    /// The port of the device's HTTP API.
+    pub port: u16,
+    pub product: Option<String>,
+    pub version: Option<String>,
+    pub device_class: Option<DeviceClass>,
+    pub protocol_capabilities: Vec<ProtocolCapability>,
+}
+
+impl DiscoveredDevice {
+    fn parse(address: IpAddr, response: &str) -> Option<Self> {
+        let mut lines = response.lines();
+        if !lines.next()?.contains(" 200 ") {
+            return None;
+        }
+
+        let headers: HashMap<String, &str> = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim()))
+            .collect();
+        let header = |name: &str| headers.get(name).map(|value| value.to_string());
+
+        Some(Self {
+            address,
+            device_type: match headers.get("content-type").copied() {
+                Some("plex/media-server") => DeviceType::Server,
+                Some("plex/media-player") => DeviceType::Player,
+                _ => DeviceType::Unknown,
+            },
+            resource_identifier: header("resource-identifier")?,
+            name: header("name")?,
+            port: headers.get("port")?.parse().ok()?,
+            product: header("product"),
+            version: header("version"),
+            device_class: headers
+                .get("device-class")
+                .and_then(|class| class.parse().ok()),
+            protocol_capabilities: headers
+                .get("protocol-capabilities")
+                .map(|capabilities| {
+                    capabilities
+                        .split(',')
+                        .filter_map(|capability| capability.trim().parse().ok())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+
+    /// The base url of the device's HTTP API.
+    pub fn url(&self) -> String {
+        format!("http://{}", SocketAddr::new(self.address, self.port))
+    }
+
+    /// Connects to the device directly on the address it answered from.
+    #[tracing::instrument(level = "debug", skip(self, client), fields(device_name = self.name))]
+    pub async fn connect(&self, mut client: HttpClient) -> Result<DeviceConnection> {
+        match self.device_type {
+            DeviceType::Server => Ok(DeviceConnection::Server(Box::new(
+                Server::new(self.url(), client).await?,
+            ))),
+            DeviceType::Player => {
+                client
+                    .x_plex_target_client_identifier
+                    .clone_from(&self.resource_identifier);
+                Ok(DeviceConnection::Player(Box::new(
+                    Player::new(self.url(), client).await?,
+                )))
+            }
+            DeviceType::Unknown => Err(crate::Error::DeviceConnectionNotSupported),
+        }
+    }
+}
+
+/// Searches the local network for servers or players.
+#[derive(Debug, Clone)]
+pub struct Discovery {
+    targets: Vec<SocketAddr>,
+    timeout: Duration,
+}
+
+impl Discovery {
+    pub fn servers() -> Self {
+        Self::new(SERVER_SEARCH_PORT)
+    }
+
+    pub fn players() -> Self {
+        Self::new(PLAYER_SEARCH_PORT)
+    }
+
+    fn new(port: u16) -> Self {
+        Self {
+            targets: vec![
+                SocketAddr::from((MULTICAST_ADDR, port)),
+                SocketAddr::from((Ipv4Addr::BROADCAST, port)),
+            ],
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Sends the search to a single address instead of the whole network.
+    pub fn set_target(self, target: SocketAddr) -> Self {
+        Self {
+            targets: vec![target],
+            ..self
+        }
+    }
+
+    /// How long to wait for answers, two seconds by default.
+    pub fn set_timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    /// Returns every device that answered before the timeout.
+    #[tracing::instrument(level = "debug")]
+    pub async fn discover(self) -> Result<Vec<DiscoveredDevice>> {
+        let (sender, receiver) = oneshot::channel();
+        thread::Builder::new()
+            .name("plex-gdm-search".to_owned())
+            .spawn(move || {
+                let _ = sender.send(self.search());
+            })?;
+
+        receiver
+            .await
+            .map_err(|_| io::Error::other("GDM search was interrupted"))?
+    }
+
+    fn search(&self) -> Result<Vec<DiscoveredDevice>> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+        socket.set_broadcast(true)?;
+
+        let mut sent = false;
+        for target in &self.targets {
+            match socket.send_to(SEARCH_REQUEST, target) {
+                Ok(_) => sent = true,
+                Err(error) => warn!(%error, %target, "Failed to send GDM search"),
+            }
+        }
+        if !sent {
+            return Err(io::Error::other("failed to send GDM search").into());
+        }
+
+        let deadline = Instant::now() + self.timeout;
+        let mut devices: Vec<DiscoveredDevice> = Vec::new();
+        let mut buf = [0; 4096];
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            socket.set_read_timeout(Some(remaining))?;
+
+            let (len, from) = match socket.recv_from(&mut buf) {
+                Ok(received) => received,
+                Err(error)
+                    if matches!(
+                        error.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    break
+                }
+                Err(error) => return Err(error.into()),
+            };
+
+            let response = String::from_utf8_lossy(&buf[..len]);
+            match DiscoveredDevice::parse(from.ip(), &response) {
+                Some(device)
+                    if !devices
+                        .iter()
+                        .any(|d| d.resource_identifier == device.resource_identifier) =>
+                {
+                    debug!(name = device.name, address = %from, "Discovered device");
+                    devices.push(device);
+                }
+                Some(_) => {}
+                None => trace!(address = %from, "Ignoring unexpected GDM response"),
+            }
+        }
+
+        Ok(devices)
+    }
+}
+
+/// Binds a socket that receives player searches from the whole local
+/// network, for use with [`Receiver::advertise`](crate::Receiver::advertise).
+pub fn player_socket() -> Result<UdpSocket> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, PLAYER_SEARCH_PORT))?;
+    socket.join_multicast_v4(&MULTICAST_ADDR, &Ipv4Addr::UNSPECIFIED)?;
+    Ok(socket)
+}
+
+/// Answers GDM searches on behalf of a player. The socket is closed when the
+/// responder is dropped.
+#[derive(Debug)]
+pub struct Responder {
+    local_addr: SocketAddr,
+    closed: Arc<AtomicBool>,
+}
+
+impl Responder {
+    pub(crate) fn start(socket: UdpSocket, resources: &PlayerResources, port: u16) -> Result<Self> {
+        let local_addr = socket.local_addr()?;
+        let closed = Arc::new(AtomicBool::new(false));
+
+        let mut response = "HTTP/1.0 200 OK\r\nContent-Type: plex/media-player\r\n".to_owned();
+        let _ = write!(
+            response,
+            "Resource-Identifier: {}\r\nName: {}\r\nPort: {port}\r\nProduct: {}\r\n\
+             Device-Class: {}\r\nProtocol: {}\r\nProtocol-Version: {}\r\n\
+             Protocol-Capabilities: {}\r\n\r\n",
+            resources.machine_identifier,
+            resources.title,
+            resources.product,
+            resources.device_class,
+            resources.protocol,
+            resources.protocol_version,
+            resources
+                .protocol_capabilities
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+
+        let responder_closed = closed.clone();
+        thread::Builder::new()
+            .name("plex-gdm-responder".to_owned())
+            .spawn(move || respond(socket, response, responder_closed))?;
+
+        Ok(Self { local_addr, closed })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for Responder {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::SeqCst);
+        // Wakes up the responder so that it notices it was closed.
+        let mut addr = self.local_addr;
+        if addr.ip().is_unspecified() {
+            addr.set_ip(Ipv4Addr::LOCALHOST.into());
+        }
+        if let Ok(socket) = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)) {
+            let _ = socket.send_to(&[], addr);
+        }
+    }
+}
+
+/// Whether the address belongs to the local network. Searches from anywhere
+/// else are ignored so that the responder can't be used to reflect traffic.
+fn is_local(ip: IpAddr) -> bool {
+    match ip.to_canonical() {
+        IpAddr::V4(ip) => ip.is_private() || ip.is_loopback() || ip.is_link_local(),
+        IpAddr::V6(ip) => ip.is_loopback() || ip.is_unique_local() || ip.is_unicast_link_local(),
+    }
+}
+
+fn respond(socket: UdpSocket, response: String, closed: Arc<AtomicBool>) {
+    let mut buf = [0; 1024];
+    loop {
+        let received = socket.recv_from(&mut buf);
+        if closed.load(Ordering::SeqCst) {
+            break;
+        }
+
+        match received {
+            Ok((_, from)) if !is_local(from.ip()) => {
+                trace!(address = %from, "Ignoring GDM search from outside the local network");
+            }
+            Ok((len, from)) if buf[..len].starts_with(b"M-SEARCH") => {
+                trace!(address = %from, "Answering GDM search");
+                if let Err(error) = socket.send_to(response.as_bytes(), from) {
+                    warn!(%error, address = %from, "Failed to answer GDM search");
+                }
+            }
+            Ok(_) => {}
+            // Windows reports an earlier answer that couldn't be delivered
+            // on the next receive.
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::ConnectionReset | io::ErrorKind::Interrupted
+                ) =>
+            {
+                warn!(%error, "Failed to receive GDM search");
+            }
+            Err(error) => {
+                warn!(%error, "Failed to receive GDM search");
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{is_local, DeviceType, DiscoveredDevice};
+    use crate::media_container::player::{DeviceClass, ProtocolCapability};
+    use std::net::{IpAddr, Ipv4Addr};
+
+    const ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20));
+
+    #[plex_api_test_helper::offline_test]
+    fn local_addresses() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.20",
+            "169.254.1.1",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:192.168.1.20",
+        ] {
+            assert!(is_local(ip.parse().unwrap()), "{ip}");
+        }
+
+        for ip in ["8.8.8.8", "100.64.0.1", "2001:db8::1", "::ffff:8.8.8.8"] {
+            assert!(!is_local(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[plex_api_test_helper::offline_test]
+    fn parse_server() {
+        let device = DiscoveredDevice::parse(
+            ADDRESS,
+            "HTTP/1.0 200 OK\r\nContent-Type: plex/media-server\r\n\
+             Resource-Identifier: machine_id3\r\nName: Basement\r\nPort: 32400\r\n\
+             Updated-At: 1690000000\r\nVersion: 1.32.5.7349-8f4248874\r\n\r\n",
+        )
+        .unwrap();
+
+        assert_eq!(device.device_type, DeviceType::Server);
+        assert_eq!(device.resource_identifier, "machine_id3");
+        assert_eq!(device.name, "Basement");
+        assert_eq!(device.port, 32400);
+        assert_eq!(device.version.as_deref(), Some("1.32.5.7349-8f4248874"));
+        assert_eq!(device.product, None);
+        assert_eq!(device.device_class, None);
+        assert!(device.protocol_capabilities.is_empty());
+        assert_eq!(device.url(), "http://192.168.1.20:32400");
+    }
+
+    #[plex_api_test_helper::offline_test]
+    fn parse_player() {
+        let device = DiscoveredDevice::parse(
+            ADDRESS,
+            "HTTP/1.0 200 OK\r\nContent-Type: plex/media-player\r\n\
+             Resource-Identifier: player_id\r\nName: Living Room\r\nPort: 32500\r\n\
+             Product: Plex HTPC\r\nVersion: 1.50.1\r\nDevice-Class: pc\r\n\
+             Protocol-Capabilities: timeline, playback,navigation\r\n\r\n",
+        )
+        .unwrap();
+
+        assert_eq!(device.device_type, DeviceType::Player);
+        assert_eq!(device.resource_identifier, "player_id");
+        assert_eq!(device.name, "Living Room");
+        assert_eq!(device.port, 32500);
+        assert_eq!(device.product.as_deref(), Some("Plex HTPC"));
+        assert_eq!(device.version.as_deref(), Some("1.50.1"));
+        assert_eq!(device.device_class, Some(DeviceClass::Pc));
+        assert_eq!(
+            device.protocol_capabilities,
+            vec![
+                ProtocolCapability::Timeline,
+                ProtocolCapability::Playback,
+                ProtocolCapability::Navigation
+            ]
+        );
+    }
+
+    #[plex_api_test_helper::offline_test]
+    fn parse_invalid() {
+        for response in [
+            "HTTP/1.1 404 Not Found\r\n\r\n",
+            "",
+            // Missing the mandatory port.
+            "HTTP/1.0 200 OK\r\nContent-Type: plex/media-server\r\n\
+             Resource-Identifier: machine_id3\r\nName: Basement\r\n\r\n",
+            "HTTP/1.0 200 OK\r\nContent-Type: plex/media-server\r\n\
+             Resource-Identifier: machine_id3\r\nName: Basement\r\nPort: nope\r\n\r\n",
+        ] {
+            assert!(
+                DiscoveredDevice::parse(ADDRESS, response).is_none(),
+                "{response:?}"
+            );
+        }
+    }
+}
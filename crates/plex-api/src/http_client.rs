@@ -1,14 +1,23 @@
 use crate::{url::MYPLEX_DEFAULT_API_URL, Result};
-use http::{uri::PathAndQuery, HeaderValue, StatusCode, Uri};
+use http::{uri::PathAndQuery, HeaderValue, Method, StatusCode, Uri};
 use isahc::{
     config::{Configurable, RedirectPolicy},
+    error::ErrorKind,
     http::request::Builder,
     AsyncBody, AsyncReadResponseExt, HttpClient as IsahcHttpClient, Request as HttpRequest,
     Response as HttpResponse,
 };
 use secrecy::{ExposeSecret, SecretString};
 use serde::{de::DeserializeOwned, Serialize};
-use std::time::Duration;
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tracing::{debug, warn};
 use uuid::Uuid;
 
 const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
@@ -16,6 +25,9 @@ const DEFAULT_CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Clone)]
 pub struct HttpClient {
+    /// The url the client was created with. Requests go to
+    /// [`base_url`](HttpClient::base_url), which differs after switching
+    /// to another connection of a server until the server is refreshed.
     pub api_url: Uri,
 
     pub http_client: IsahcHttpClient,
@@ -86,6 +98,60 @@ pub struct HttpClient {
     ///
     /// Used when proxying a client request via a server.
     pub x_plex_target_client_identifier: String,
+
+    /// Other urls of the same server to switch to when `api_url` stops
+    /// answering.
+    failover: Option<Arc<Failover>>,
+}
+
+/// The connections of a server in the order of preference, see
+/// [`ConnectionStrategy`](crate::device::ConnectionStrategy).
+pub(crate) struct Failover {
+    urls: Vec<Uri>,
+    active: AtomicUsize,
+    /// Called with the url requests switched to.
+    on_switch: Box<dyn Fn(&Uri) + Send + Sync>,
+}
+
+impl fmt::Debug for Failover {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Failover")
+            .field("urls", &self.urls)
+            .field("active", &self.active)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Failover {
+    pub(crate) fn new<F>(urls: Vec<Uri>, active: usize, on_switch: F) -> Self
+    where
+        F: Fn(&Uri) + Send + Sync + 'static,
+    {
+        Self {
+            urls,
+            active: AtomicUsize::new(active),
+            on_switch: Box::new(on_switch),
+        }
+    }
+
+    fn active_url(&self) -> &Uri {
+        &self.urls[self.active.load(Ordering::SeqCst)]
+    }
+
+    fn switch_to(&self, index: usize) {
+        self.active.store(index, Ordering::SeqCst);
+        (self.on_switch)(&self.urls[index]);
+    }
+}
+
+/// Whether the request might succeed using a different connection. Only
+/// requests that can't have had any effect on the server are retried.
+fn should_fail_over(error: &isahc::Error, method: &Method) -> bool {
+    match error.kind() {
+        ErrorKind::ConnectionFailed | ErrorKind::NameResolution => true,
+        ErrorKind::Timeout | ErrorKind::Io => method == Method::GET,
+        _ => false,
+    }
 }
 
 impl HttpClient {
@@ -121,6 +187,24 @@ impl HttpClient {
         request
     }
 
+    /// The url requests are sent to. It's `api_url` unless the client had to
+    /// switch to another connection of the server.
+    pub fn base_url(&self) -> Uri {
+        match self.failover {
+            Some(ref failover) => failover.active_url().clone(),
+            None => self.api_url.clone(),
+        }
+    }
+
+    pub(crate) fn set_failover(&mut self, failover: Failover) {
+        self.failover = Some(Arc::new(failover));
+    }
+
+    /// Points `api_url` at the connection the client switched to.
+    pub(crate) fn follow_failover(&mut self) {
+        self.api_url = self.base_url();
+    }
+
     /// Verifies that this client has an authentication token.
     pub fn is_authenticated(&self) -> bool {
         !self.x_plex_token.expose_secret().is_empty()
@@ -134,7 +218,8 @@ impl HttpClient {
     {
         RequestBuilder {
             http_client: &self.http_client,
-            base_url: self.api_url.clone(),
+            failover: self.failover.as_deref(),
+            base_url: self.base_url(),
             path_and_query: path,
             request_builder: self.prepare_request().method("POST"),
             timeout: Some(DEFAULT_TIMEOUT),
@@ -150,7 +235,8 @@ impl HttpClient {
     {
         RequestBuilder {
             http_client: &self.http_client,
-            base_url: self.api_url.clone(),
+            failover: self.failover.as_deref(),
+            base_url: self.base_url(),
             path_and_query: path,
             request_builder: self.prepare_request_min().method("POST"),
             timeout: Some(DEFAULT_TIMEOUT),
@@ -165,7 +251,8 @@ impl HttpClient {
     {
         RequestBuilder {
             http_client: &self.http_client,
-            base_url: self.api_url.clone(),
+            failover: self.failover.as_deref(),
+            base_url: self.base_url(),
             path_and_query: path,
             request_builder: self.prepare_request().method("GET"),
             timeout: Some(DEFAULT_TIMEOUT),
@@ -181,7 +268,8 @@ impl HttpClient {
     {
         RequestBuilder {
             http_client: &self.http_client,
-            base_url: self.api_url.clone(),
+            failover: self.failover.as_deref(),
+            base_url: self.base_url(),
             path_and_query: path,
             request_builder: self.prepare_request_min().method("GET"),
             timeout: Some(DEFAULT_TIMEOUT),
@@ -196,7 +284,8 @@ impl HttpClient {
     {
         RequestBuilder {
             http_client: &self.http_client,
-            base_url: self.api_url.clone(),
+            failover: self.failover.as_deref(),
+            base_url: self.base_url(),
             path_and_query: path,
             request_builder: self.prepare_request().method("PUT"),
             timeout: Some(DEFAULT_TIMEOUT),
@@ -212,7 +301,8 @@ impl HttpClient {
     {
         RequestBuilder {
             http_client: &self.http_client,
-            base_url: self.api_url.clone(),
+            failover: self.failover.as_deref(),
+            base_url: self.base_url(),
             path_and_query: path,
             request_builder: self.prepare_request_min().method("PUT"),
             timeout: Some(DEFAULT_TIMEOUT),
@@ -227,7 +317,8 @@ impl HttpClient {
     {
         RequestBuilder {
             http_client: &self.http_client,
-            base_url: self.api_url.clone(),
+            failover: self.failover.as_deref(),
+            base_url: self.base_url(),
             path_and_query: path,
             request_builder: self.prepare_request().method("DELETE"),
             timeout: Some(DEFAULT_TIMEOUT),
@@ -243,7 +334,8 @@ impl HttpClient {
     {
         RequestBuilder {
             http_client: &self.http_client,
-            base_url: self.api_url.clone(),
+            failover: self.failover.as_deref(),
+            base_url: self.base_url(),
             path_and_query: path,
             request_builder: self.prepare_request_min().method("DELETE"),
             timeout: Some(DEFAULT_TIMEOUT),
@@ -279,6 +371,7 @@ where
     <PathAndQuery as TryFrom<P>>::Error: Into<http::Error>,
 {
     http_client: &'a IsahcHttpClient,
+    failover: Option<&'a Failover>,
     base_url: Uri,
     path_and_query: P,
     request_builder: Builder,
@@ -295,6 +388,7 @@ where
     pub fn timeout(self, timeout: Option<Duration>) -> Self {
         Self {
             http_client: self.http_client,
+            failover: self.failover,
             base_url: self.base_url,
             path_and_query: self.path_and_query,
             request_builder: self.request_builder,
@@ -304,6 +398,19 @@ where
 
     /// Adds a body to the request.
     pub fn body<B>(self, body: B) -> Result<Request<'a, B>>
+    where
+        B: Into<AsyncBody>,
+    {
+        self.build(body, false)
+    }
+
+    /// Builds a request without a body, which can be sent again using
+    /// another connection when the current one fails.
+    fn empty(self) -> Result<Request<'a, ()>> {
+        self.build((), true)
+    }
+
+    fn build<B>(self, body: B, replayable: bool) -> Result<Request<'a, B>>
     where
         B: Into<AsyncBody>,
     {
@@ -319,6 +426,8 @@ where
 
         Ok(Request {
             http_client: self.http_client,
+            failover: self.failover.filter(|_| replayable),
+            timeout: self.timeout,
             request: builder.body(body)?,
         })
     }
@@ -352,6 +461,7 @@ where
     {
         Self {
             http_client: self.http_client,
+            failover: self.failover,
             base_url: self.base_url,
             path_and_query: self.path_and_query,
             request_builder: self.request_builder.header(key, value),
@@ -361,17 +471,17 @@ where
 
     /// Sends this request generating a response.
     pub async fn send(self) -> Result<HttpResponse<AsyncBody>> {
-        self.body(())?.send().await
+        self.empty()?.send().await
     }
 
     /// Sends this request and attempts to decode the response as JSON.
     pub async fn json<T: DeserializeOwned + Unpin>(self) -> Result<T> {
-        self.body(())?.json().await
+        self.empty()?.json().await
     }
 
     /// Sends this request and attempts to decode the response as XML.
     pub async fn xml<T: DeserializeOwned + Unpin>(self) -> Result<T> {
-        self.body(())?.xml().await
+        self.empty()?.xml().await
     }
 
     /// Sends this request, verifies success and then consumes any response.
@@ -390,6 +500,8 @@ where
 
 pub struct Request<'a, T> {
     http_client: &'a IsahcHttpClient,
+    failover: Option<&'a Failover>,
+    timeout: Option<Duration>,
     request: HttpRequest<T>,
 }
 
@@ -399,7 +511,51 @@ where
 {
     /// Sends this request generating a response.
     pub async fn send(self) -> Result<HttpResponse<AsyncBody>> {
-        Ok(self.http_client.send_async(self.request).await?)
+        let Some(failover) = self.failover else {
+            return Ok(self.http_client.send_async(self.request).await?);
+        };
+
+        let failed = failover.active.load(Ordering::SeqCst);
+        let method = self.request.method().clone();
+        let headers = self.request.headers().clone();
+        let path_and_query = self.request.uri().path_and_query().cloned();
+
+        let error = match self.http_client.send_async(self.request).await {
+            Ok(response) => return Ok(response),
+            Err(error) if should_fail_over(&error, &method) => error,
+            Err(error) => return Err(error.into()),
+        };
+
+        for (index, url) in failover.urls.iter().enumerate() {
+            if index == failed {
+                continue;
+            }
+
+            let mut uri_parts = url.clone().into_parts();
+            uri_parts.path_and_query.clone_from(&path_and_query);
+            let uri = Uri::from_parts(uri_parts).map_err(Into::<http::Error>::into)?;
+            debug!(%uri, %error, "Retrying request using another connection");
+
+            let mut builder = HttpRequest::builder().method(method.clone()).uri(uri);
+            if let Some(request_headers) = builder.headers_mut() {
+                request_headers.extend(headers.clone());
+            }
+            if let Some(timeout) = self.timeout {
+                builder = builder.timeout(timeout);
+            }
+
+            match self.http_client.send_async(builder.body(())?).await {
+                Ok(response) => {
+                    warn!(%url, "Switched to another connection of the server");
+                    failover.switch_to(index);
+                    return Ok(response);
+                }
+                Err(error) if should_fail_over(&error, &method) => continue,
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        Err(error.into())
     }
 
     /// Sends this request and attempts to decode the response as JSON.
@@ -493,6 +649,7 @@ impl Default for HttpClientBuilder {
             x_plex_model: String::from("hosted"),
             x_plex_features: String::from("external-media,indirect-media,hub-style-list"),
             x_plex_target_client_identifier: String::from(""),
+            failover: None,
         };
 
         Self { client: Ok(client) }
@@ -539,6 +696,7 @@ impl HttpClientBuilder {
         Self {
             client: self.client.and_then(move |mut client| {
                 client.api_url = Uri::try_from(api_url).map_err(Into::into)?;
+                client.failover = None;
                 Ok(client)
             }),
         }
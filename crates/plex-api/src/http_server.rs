@@ -0,0 +1,186 @@
+//! Just enough of HTTP/1.1 to receive requests from Plex apps on the local
+//! network, e.g. timelines pushed by players or Companion commands.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream},
+    time::Duration,
+};
+
+/// How long to wait for the rest of a request once a client connected.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+/// The longest request line or header line accepted, including the line end.
+const MAX_LINE_LENGTH: u64 = 8 * 1024;
+/// The most header lines accepted in a request.
+const MAX_HEADERS: usize = 64;
+
+#[derive(Debug)]
+pub(crate) struct Request {
+    pub(crate) method: String,
+    pub(crate) path: String,
+    pub(crate) query: HashMap<String, String>,
+    /// Header names are lowercase.
+    pub(crate) headers: HashMap<String, String>,
+    pub(crate) body: Vec<u8>,
+}
+
+impl Request {
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    pub(crate) fn param(&self, name: &str) -> Option<&str> {
+        self.query.get(name).map(String::as_str)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum RequestError {
+    Io(io::Error),
+    Malformed,
+    HeadersTooLarge,
+    BodyTooLarge,
+}
+
+impl RequestError {
+    /// The status to answer the client with, if it is still worth answering.
+    pub(crate) fn status(&self) -> Option<u16> {
+        match self {
+            RequestError::Io(_) => None,
+            RequestError::Malformed => Some(400),
+            RequestError::HeadersTooLarge => Some(431),
+            RequestError::BodyTooLarge => Some(413),
+        }
+    }
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestError::Io(error) => error.fmt(f),
+            RequestError::Malformed => f.write_str("malformed request"),
+            RequestError::HeadersTooLarge => f.write_str("request headers too large"),
+            RequestError::BodyTooLarge => f.write_str("request body too large"),
+        }
+    }
+}
+
+impl From<io::Error> for RequestError {
+    fn from(error: io::Error) -> Self {
+        RequestError::Io(error)
+    }
+}
+
+impl From<RequestError> for io::Error {
+    fn from(error: RequestError) -> Self {
+        match error {
+            RequestError::Io(error) => error,
+            error => io::Error::new(io::ErrorKind::InvalidData, error.to_string()),
+        }
+    }
+}
+
+/// Reads a line without buffering more than [`MAX_LINE_LENGTH`] of it.
+fn read_line<R: BufRead>(reader: &mut R, line: &mut String) -> Result<(), RequestError> {
+    line.clear();
+    let read = reader.take(MAX_LINE_LENGTH).read_line(line)?;
+    if read as u64 == MAX_LINE_LENGTH && !line.ends_with('\n') {
+        return Err(RequestError::HeadersTooLarge);
+    }
+    Ok(())
+}
+
+/// Reads a request, refusing bodies larger than `max_body` bytes.
+pub(crate) fn read_request(stream: &TcpStream, max_body: usize) -> Result<Request, RequestError> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    read_line(&mut reader, &mut line)?;
+
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_owned();
+    let target = parts.next().unwrap_or_default();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let path = path.to_owned();
+    let query = serde_urlencoded::from_str(query).map_err(|_| RequestError::Malformed)?;
+
+    let mut headers = HashMap::new();
+    loop {
+        read_line(&mut reader, &mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if headers.len() == MAX_HEADERS {
+            return Err(RequestError::HeadersTooLarge);
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_owned());
+        }
+    }
+
+    let content_length = match headers.get("content-length") {
+        Some(value) => value.parse::<u64>().map_err(|_| RequestError::Malformed)?,
+        None => 0,
+    };
+    if content_length > max_body as u64 {
+        return Err(RequestError::BodyTooLarge);
+    }
+    let mut body = vec![0; content_length as usize];
+    reader.read_exact(&mut body)?;
+
+    Ok(Request {
+        method,
+        path,
+        query,
+        headers,
+        body,
+    })
+}
+
+pub(crate) fn write_response(
+    mut stream: &TcpStream,
+    status: u16,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    };
+
+    let mut response = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        body.len()
+    );
+    for (name, value) in headers {
+        response.push_str(&format!("{name}: {value}\r\n"));
+    }
+    response.push_str("\r\n");
+    response.push_str(body);
+
+    stream.write_all(response.as_bytes())
+}
+
+/// Connects to a listener so that its blocking accept returns, e.g. to let it
+/// notice that it was closed. Listeners bound to all interfaces are reached
+/// over loopback, connecting to an unspecified address fails on Windows.
+pub(crate) fn wake_listener(mut addr: SocketAddr) {
+    if addr.ip().is_unspecified() {
+        addr.set_ip(match addr {
+            SocketAddr::V4(_) => Ipv4Addr::LOCALHOST.into(),
+            SocketAddr::V6(_) => Ipv6Addr::LOCALHOST.into(),
+        });
+    }
+    let _ = TcpStream::connect(addr);
+}
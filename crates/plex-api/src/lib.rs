@@ -8,10 +8,13 @@
 //! TODO
 //!
 mod error;
+pub mod gdm;
 mod http_client;
+mod http_server;
 pub mod media_container;
 mod myplex;
 mod player;
+mod receiver;
 mod server;
 pub mod url;
 pub mod webhook;
@@ -19,9 +22,19 @@ pub mod webhook;
 pub use error::Error;
 pub use http_client::{HttpClient, HttpClientBuilder};
 pub use myplex::{
-    account::RestrictionProfile, device, discover, pin::PinManager, sharing, MyPlex, MyPlexBuilder,
+    account::{
+        AutoSelectSubtitleMode, DefaultSubtitleAccessibility, DefaultSubtitleForced, Profile,
+        RestrictionProfile,
+    },
+    device, discover,
+    pin::PinManager,
+    sharing, MyPlex, MyPlexBuilder,
+};
+pub use player::{NavigationCommand, PlaybackType, Player, TimelineSubscription};
+pub use receiver::{PlayMediaRequest, Receiver, ReceiverHandler};
+pub use server::{
+    activity, history, hub, library, notification, play_queue, playback, prefs::Preferences,
+    session, transcode, Server,
 };
-pub use player::Player;
-pub use server::{library, prefs::Preferences, transcode, Server};
 
 pub type Result<T = (), E = error::Error> = std::result::Result<T, E>;
@@ -132,6 +132,237 @@ pub struct Connection {
     pub relay: Option<bool>,
 }
 
+/// A device as listed by plex.tv's `/api/v2/resources`.
+#[serde_as]
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct Resource {
+    pub name: String,
+    pub product: String,
+    pub product_version: String,
+    pub platform: Option<String>,
+    pub platform_version: Option<String>,
+    pub device: Option<String>,
+    pub client_identifier: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub last_seen_at: OffsetDateTime,
+    #[serde_as(as = "StringWithSeparator::<CommaSeparator, Feature>")]
+    pub provides: Vec<Feature>,
+    /// The account sharing the device, unless it's owned.
+    pub owner_id: Option<u64>,
+    /// The name of the account sharing the device.
+    pub source_title: Option<String>,
+    pub public_address: Option<String>,
+    pub access_token: Option<SecretString>,
+    pub owned: bool,
+    /// Whether the device belongs to a member of the Plex Home.
+    pub home: bool,
+    pub synced: bool,
+    /// Whether connections can be relayed through plex.tv.
+    pub relay: bool,
+    /// Whether the device is currently online.
+    pub presence: bool,
+    pub https_required: bool,
+    /// Whether the device has the same public address as the client, i.e.
+    /// they're probably on the same network.
+    pub public_address_matches: bool,
+    pub dns_rebinding_protection: Option<bool>,
+    pub nat_loopback_supported: Option<bool>,
+    #[serde(default)]
+    pub connections: Vec<ResourceConnection>,
+    /// Only known for devices from `devices.xml`, which is needed for
+    /// renaming and removing them.
+    #[serde(skip)]
+    pub id: Option<u32>,
+    #[serde(skip)]
+    pub sync_list: Option<SyncList>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub struct ResourceConnection {
+    pub protocol: String,
+    pub address: String,
+    pub port: u16,
+    #[serde(with = "http_serde::uri")]
+    pub uri: http::Uri,
+    pub local: bool,
+    pub relay: bool,
+    #[serde(rename = "IPv6")]
+    pub ipv6: bool,
+}
+
+impl From<Device> for Resource {
+    fn from(device: Device) -> Self {
+        Self {
+            name: device.name,
+            product: device.product,
+            product_version: device.product_version,
+            platform: Some(device.platform),
+            platform_version: Some(device.platform_version),
+            device: Some(device.device),
+            client_identifier: device.client_identifier,
+            created_at: device.created_at,
+            last_seen_at: device.last_seen_at,
+            provides: device.provides,
+            owner_id: device.owner_id,
+            source_title: device.source_title,
+            public_address: Some(device.public_address),
+            access_token: device.access_token,
+            owned: device.owned.unwrap_or_default(),
+            home: device.home.unwrap_or_default(),
+            synced: device.synced.unwrap_or_default(),
+            relay: device.relay.unwrap_or_default(),
+            presence: device.presence.unwrap_or_default(),
+            https_required: device.https_required.unwrap_or_default(),
+            public_address_matches: device.public_address_matches.unwrap_or_default(),
+            dns_rebinding_protection: device.dns_rebinding_protection,
+            nat_loopback_supported: device.nat_loopback_supported,
+            connections: device.connections.into_iter().map(Into::into).collect(),
+            id: device.id,
+            sync_list: device.sync_list,
+        }
+    }
+}
+
+impl From<Connection> for ResourceConnection {
+    fn from(connection: Connection) -> Self {
+        let uri = connection.uri;
+        Self {
+            protocol: connection
+                .protocol
+                .or_else(|| uri.scheme_str().map(ToOwned::to_owned))
+                .unwrap_or_default(),
+            address: connection
+                .address
+                .or_else(|| uri.host().map(ToOwned::to_owned))
+                .unwrap_or_default(),
+            port: connection
+                .port
+                .and_then(|port| port.try_into().ok())
+                .or_else(|| uri.port_u16())
+                .unwrap_or_default(),
+            ipv6: uri.host().is_some_and(|host| host.starts_with('[')),
+            local: connection.local.unwrap_or_default(),
+            relay: connection.relay.unwrap_or_default(),
+            uri,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub struct SyncItemsMediaContainer {
+    #[serde(rename = "SyncItem", default)]
+    pub items: Vec<SyncItem>,
+    #[serde(rename = "@clientIdentifier")]
+    pub client_identifier: Option<String>,
+    #[serde(rename = "@status")]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub struct SyncItem {
+    #[serde(rename = "@id")]
+    pub id: u32,
+    #[serde(rename = "@version")]
+    pub version: u32,
+    #[serde(rename = "@rootTitle")]
+    pub root_title: String,
+    #[serde(rename = "@title")]
+    pub title: String,
+    #[serde(rename = "@metadataType")]
+    pub metadata_type: String,
+    #[serde(rename = "@contentType")]
+    pub content_type: String,
+    /// The server the synced media comes from.
+    #[serde(rename = "@machineIdentifier")]
+    pub machine_identifier: String,
+    #[serde(rename = "Server")]
+    pub server: Option<SyncItemServer>,
+    #[serde(rename = "Status")]
+    pub status: Option<SyncItemStatus>,
+    #[serde(rename = "MediaSettings")]
+    pub media_settings: Option<SyncItemMediaSettings>,
+    #[serde(rename = "Policy")]
+    pub policy: Option<SyncItemPolicy>,
+    #[serde(rename = "Location")]
+    pub location: Option<SyncItemLocation>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub struct SyncItemServer {
+    #[serde(rename = "@machineIdentifier")]
+    pub machine_identifier: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub struct SyncItemStatus {
+    #[serde(rename = "@failureCode")]
+    pub failure_code: Option<String>,
+    #[serde(rename = "@failure")]
+    pub failure: Option<String>,
+    #[serde(rename = "@state")]
+    pub state: String,
+    #[serde(rename = "@itemsCount")]
+    pub items_count: u32,
+    #[serde(rename = "@itemsCompleteCount")]
+    pub items_complete_count: u32,
+    #[serde(rename = "@itemsSuccessfulCount")]
+    pub items_successful_count: u32,
+    #[serde(rename = "@itemsDownloadedCount")]
+    pub items_downloaded_count: u32,
+    #[serde(rename = "@itemsReadyCount")]
+    pub items_ready_count: u32,
+    #[serde(rename = "@totalSize")]
+    pub total_size: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub struct SyncItemMediaSettings {
+    #[serde(rename = "@audioBoost")]
+    pub audio_boost: Option<u32>,
+    #[serde(rename = "@maxVideoBitrate")]
+    pub max_video_bitrate: Option<u32>,
+    #[serde(rename = "@musicBitrate")]
+    pub music_bitrate: Option<u32>,
+    #[serde(rename = "@photoQuality")]
+    pub photo_quality: Option<u32>,
+    #[serde(rename = "@photoResolution")]
+    pub photo_resolution: Option<String>,
+    #[serde(rename = "@subtitleSize")]
+    pub subtitle_size: Option<u32>,
+    #[serde(rename = "@videoQuality")]
+    pub video_quality: Option<u32>,
+    #[serde(rename = "@videoResolution")]
+    pub video_resolution: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub struct SyncItemPolicy {
+    #[serde(rename = "@scope")]
+    pub scope: String,
+    #[serde(rename = "@unwatched")]
+    pub unwatched: bool,
+    #[serde(rename = "@value")]
+    pub value: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub struct SyncItemLocation {
+    #[serde(rename = "@uri")]
+    pub uri: String,
+}
+
 #[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub enum Feature {
@@ -1,18 +1,18 @@
-use serde::Deserialize;
-use serde_plain::derive_fromstr_from_deserialize;
+use serde::{Deserialize, Serialize};
+use serde_plain::{derive_display_from_serialize, derive_fromstr_from_deserialize};
 use serde_with::{formats::CommaSeparator, serde_as, StringWithSeparator};
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
 pub struct ResourcesMediaContainer {
     #[serde(rename = "Player")]
     pub player: Player,
-    #[serde(rename = "@size")]
+    #[serde(rename = "@size", skip_serializing_if = "Option::is_none")]
     pub size: Option<u32>,
 }
 
 #[serde_as]
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
 pub struct Player {
     #[serde(rename = "@machineIdentifier")]
@@ -36,20 +36,26 @@ pub struct Player {
     pub protocol_capabilities: Vec<ProtocolCapability>,
 }
 
-#[derive(Debug, Deserialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub enum DeviceClass {
     Stb,
     Phone,
+    Tablet,
+    Pc,
     #[cfg(not(feature = "tests_deny_unknown_fields"))]
     #[serde(other)]
     Unknown,
 }
 
-#[derive(Debug, Deserialize, Clone, Copy)]
+derive_fromstr_from_deserialize!(DeviceClass);
+derive_display_from_serialize!(DeviceClass);
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub enum ProtocolCapability {
     Mirror,
+    Navigation,
     Playback,
     #[serde(rename = "playqueues")]
     PlayQueues,
@@ -61,3 +67,178 @@ pub enum ProtocolCapability {
 }
 
 derive_fromstr_from_deserialize!(ProtocolCapability);
+derive_display_from_serialize!(ProtocolCapability);
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaybackType {
+    Video,
+    Music,
+    Photo,
+    #[cfg(not(feature = "tests_deny_unknown_fields"))]
+    #[serde(other)]
+    Unknown,
+}
+
+derive_fromstr_from_deserialize!(PlaybackType);
+derive_display_from_serialize!(PlaybackType);
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaybackState {
+    Playing,
+    Paused,
+    Buffering,
+    Stopped,
+    #[cfg(not(feature = "tests_deny_unknown_fields"))]
+    #[serde(other)]
+    Unknown,
+}
+
+derive_fromstr_from_deserialize!(PlaybackState);
+derive_display_from_serialize!(PlaybackState);
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ControllableFeature {
+    PlayPause,
+    Stop,
+    Volume,
+    Shuffle,
+    Repeat,
+    SeekTo,
+    SkipPrevious,
+    SkipNext,
+    StepBack,
+    StepForward,
+    SubtitleStream,
+    AudioStream,
+    #[cfg(not(feature = "tests_deny_unknown_fields"))]
+    #[serde(other)]
+    Unknown,
+}
+
+derive_fromstr_from_deserialize!(ControllableFeature);
+derive_display_from_serialize!(ControllableFeature);
+
+/// What a player is doing, with one timeline for each type of media.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub struct TimelineMediaContainer {
+    #[serde(rename = "@commandID", skip_serializing_if = "Option::is_none")]
+    pub command_id: Option<u64>,
+    /// The screen the player shows, e.g. `navigation` or `fullScreenVideo`.
+    #[serde(rename = "@location", skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(rename = "@size", skip_serializing_if = "Option::is_none")]
+    pub size: Option<u32>,
+    #[serde(default, rename = "Timeline")]
+    pub timelines: Vec<Timeline>,
+}
+
+impl TimelineMediaContainer {
+    pub fn new(command_id: Option<u64>, timelines: Vec<Timeline>) -> Self {
+        Self {
+            command_id,
+            location: None,
+            size: Some(timelines.len() as u32),
+            timelines,
+        }
+    }
+
+    /// Returns the timeline of the media type.
+    pub fn timeline(&self, playback_type: PlaybackType) -> Option<&Timeline> {
+        self.timelines
+            .iter()
+            .find(|timeline| timeline.playback_type == playback_type)
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub struct Timeline {
+    #[serde(rename = "@type")]
+    pub playback_type: PlaybackType,
+    #[serde(rename = "@state")]
+    pub state: PlaybackState,
+    /// The playback position in milliseconds.
+    #[serde(rename = "@time", skip_serializing_if = "Option::is_none")]
+    pub time: Option<u64>,
+    #[serde(rename = "@duration", skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u64>,
+    #[serde(rename = "@ratingKey", skip_serializing_if = "Option::is_none")]
+    pub rating_key: Option<String>,
+    #[serde(rename = "@key", skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(rename = "@volume", skip_serializing_if = "Option::is_none")]
+    pub volume: Option<u8>,
+    #[serde(rename = "@mute", skip_serializing_if = "Option::is_none")]
+    pub mute: Option<bool>,
+    #[serde(rename = "@shuffle", skip_serializing_if = "Option::is_none")]
+    pub shuffle: Option<bool>,
+    /// 0 for off, 1 to repeat the item and 2 to repeat all items.
+    #[serde(rename = "@repeat", skip_serializing_if = "Option::is_none")]
+    pub repeat: Option<u8>,
+    /// The commands the player accepts for this media type.
+    #[serde_as(as = "StringWithSeparator::<CommaSeparator, ControllableFeature>")]
+    #[serde(
+        default,
+        rename = "@controllable",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub controllable: Vec<ControllableFeature>,
+    #[serde(rename = "@seekRange", skip_serializing_if = "Option::is_none")]
+    pub seek_range: Option<String>,
+    /// The server the media is played from.
+    #[serde(rename = "@machineIdentifier", skip_serializing_if = "Option::is_none")]
+    pub machine_identifier: Option<String>,
+    #[serde(rename = "@address", skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(rename = "@port", skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(rename = "@protocol", skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+    #[serde(rename = "@containerKey", skip_serializing_if = "Option::is_none")]
+    pub container_key: Option<String>,
+    #[serde(rename = "@playQueueID", skip_serializing_if = "Option::is_none")]
+    pub play_queue_id: Option<u64>,
+    #[serde(rename = "@playQueueItemID", skip_serializing_if = "Option::is_none")]
+    pub play_queue_item_id: Option<u64>,
+    #[serde(rename = "@playQueueVersion", skip_serializing_if = "Option::is_none")]
+    pub play_queue_version: Option<u32>,
+    #[serde(rename = "@audioStreamID", skip_serializing_if = "Option::is_none")]
+    pub audio_stream_id: Option<u64>,
+    #[serde(rename = "@subtitleStreamID", skip_serializing_if = "Option::is_none")]
+    pub subtitle_stream_id: Option<u64>,
+}
+
+impl Timeline {
+    /// Creates a timeline with just the type and state of playback.
+    pub fn new(playback_type: PlaybackType, state: PlaybackState) -> Self {
+        Self {
+            playback_type,
+            state,
+            time: None,
+            duration: None,
+            rating_key: None,
+            key: None,
+            volume: None,
+            mute: None,
+            shuffle: None,
+            repeat: None,
+            controllable: Vec::new(),
+            seek_range: None,
+            machine_identifier: None,
+            address: None,
+            port: None,
+            protocol: None,
+            container_key: None,
+            play_queue_id: None,
+            play_queue_item_id: None,
+            play_queue_version: None,
+            audio_stream_id: None,
+            subtitle_stream_id: None,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::media_container::{helpers::deserialize_option_string_from_number, MediaContainer};
+use serde::Deserialize;
+
+/// A long-running task performed by the server, e.g. a library scan.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct Activity {
+    pub uuid: String,
+    /// The kind of the activity, e.g. `library.update.section`.
+    #[serde(rename = "type")]
+    pub activity_type: String,
+    pub cancellable: bool,
+    #[serde(rename = "userID")]
+    pub user_id: u64,
+    pub title: String,
+    pub subtitle: Option<String>,
+    /// Percentage complete, or -1 if unknown.
+    pub progress: i32,
+    #[serde(rename = "Context")]
+    pub context: Option<ActivityContext>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityContext {
+    #[serde(
+        default,
+        rename = "librarySectionID",
+        deserialize_with = "deserialize_option_string_from_number"
+    )]
+    pub library_section_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct ActivitiesMediaContainer {
+    #[serde(flatten)]
+    pub media_container: MediaContainer,
+
+    #[serde(default, rename = "Activity")]
+    pub activities: Vec<Activity>,
+}
@@ -0,0 +1,33 @@
+use serde::Deserialize;
+
+/// A maintenance task the server runs on a schedule, e.g. backing up the
+/// database.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct ButlerTask {
+    /// The name used to start or stop the task, e.g. `BackupDatabase`.
+    pub name: String,
+    /// How often the task runs, in days.
+    pub interval: u32,
+    pub schedule_randomized: bool,
+    pub enabled: bool,
+    pub title: String,
+    pub description: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub struct ButlerTasks {
+    #[serde(default, rename = "ButlerTask")]
+    pub tasks: Vec<ButlerTask>,
+}
+
+/// Unlike most of the responses the list of tasks isn't wrapped in a
+/// `MediaContainer`.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub(crate) struct ButlerTasksWrapper {
+    #[serde(rename = "ButlerTasks")]
+    pub(crate) butler_tasks: ButlerTasks,
+}
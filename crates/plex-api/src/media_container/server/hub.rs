@@ -0,0 +1,56 @@
+use super::library::Metadata;
+use crate::media_container::{helpers::optional_boolish, MediaContainer};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// A list of items grouped by the server, e.g. "Continue Watching" or
+/// "Recently Added Movies".
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct Hub {
+    /// The path listing all of the items in the hub.
+    pub key: Option<String>,
+    /// The path listing only the items included with the hub.
+    pub hub_key: Option<String>,
+    pub title: String,
+    #[serde(rename = "type")]
+    pub hub_type: String,
+    pub subtype: Option<String>,
+    pub hub_identifier: String,
+    pub context: String,
+    pub size: u32,
+    #[serde(default, deserialize_with = "optional_boolish")]
+    pub more: Option<bool>,
+    pub style: Option<String>,
+    #[serde(default, deserialize_with = "optional_boolish")]
+    pub promoted: Option<bool>,
+    #[serde(default, deserialize_with = "optional_boolish")]
+    pub random: Option<bool>,
+
+    #[serde(default, rename = "Metadata")]
+    pub metadata: Vec<Metadata>,
+    /// Search hubs for tags (e.g. actors or genres) list the tags here
+    /// instead of in `metadata`.
+    #[serde(default, rename = "Directory")]
+    pub directories: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct HubsMediaContainer {
+    #[serde(default, deserialize_with = "optional_boolish")]
+    pub allow_sync: Option<bool>,
+    #[serde(rename = "librarySectionID")]
+    pub library_section_id: Option<u32>,
+    pub library_section_title: Option<String>,
+    #[serde(rename = "librarySectionUUID")]
+    pub library_section_uuid: Option<String>,
+
+    #[serde(flatten)]
+    pub media_container: MediaContainer,
+
+    #[serde(default, rename = "Hub")]
+    pub hubs: Vec<Hub>,
+}
@@ -0,0 +1,39 @@
+use crate::media_container::MediaContainer;
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+/// An image or theme song that can be selected for an item, as returned by
+/// `/library/metadata/{id}/posters` and the like.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct Artwork {
+    pub key: String,
+    /// Identifies the artwork when selecting it.
+    pub rating_key: String,
+    pub thumb: Option<String>,
+    #[serde(default)]
+    pub selected: bool,
+    /// The agent the artwork comes from, not set for uploaded artwork.
+    pub provider: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct ArtworkMediaContainer {
+    #[serde(flatten)]
+    pub media_container: MediaContainer,
+
+    #[serde(rename = "librarySectionID")]
+    pub library_section_id: Option<u32>,
+    pub library_section_title: Option<String>,
+    #[serde(rename = "librarySectionUUID")]
+    pub library_section_uuid: Option<String>,
+    pub media_tag_prefix: Option<String>,
+    #[serde(default, with = "time::serde::timestamp::option")]
+    pub media_tag_version: Option<OffsetDateTime>,
+
+    #[serde(default, rename = "Metadata")]
+    pub artwork: Vec<Artwork>,
+}
@@ -5,7 +5,7 @@ use serde::{
     Deserialize, Deserializer,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Guid {
     Local(String),
     Imdb(String),
@@ -0,0 +1,34 @@
+use crate::media_container::MediaContainer;
+use serde::Deserialize;
+
+/// A possible match for an item, as returned by
+/// `/library/metadata/{id}/matches`.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    /// The guid to pass back to the server when applying the match.
+    pub guid: String,
+    pub name: String,
+    pub year: Option<u32>,
+    /// How well the result matches the search, 100 being the best.
+    pub score: u32,
+    /// Whether the item is currently matched to this result.
+    #[serde(default)]
+    pub matched: bool,
+    pub thumb: Option<String>,
+    pub summary: Option<String>,
+    #[serde(rename = "type")]
+    pub result_type: Option<String>,
+    pub lifespan_ended: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub struct SearchResultsMediaContainer {
+    #[serde(flatten)]
+    pub media_container: MediaContainer,
+
+    #[serde(default, rename = "SearchResult")]
+    pub results: Vec<SearchResult>,
+}
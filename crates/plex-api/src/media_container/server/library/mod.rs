@@ -1,15 +1,27 @@
+mod artwork;
 mod guid;
+mod matching;
 mod metadata_type;
-
-use crate::media_container::{
-    helpers::deserialize_option_string_from_number,
-    helpers::{deserialize_option_datetime_from_timestamp, optional_boolish},
-    preferences::Preferences,
-    MediaContainer,
+mod search;
+mod section;
+
+use crate::{
+    media_container::{
+        helpers::deserialize_option_string_from_number,
+        helpers::{deserialize_option_datetime_from_timestamp, optional_boolish},
+        preferences::Preferences,
+        server::session::{Player, Session, User},
+        MediaContainer,
+    },
+    transcode::TranscodeSessionStats,
 };
+pub use artwork::*;
 pub use guid::Guid;
+pub use matching::*;
 pub use metadata_type::*;
 use monostate::MustBe;
+pub use search::*;
+pub use section::*;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_aux::prelude::{
     deserialize_number_from_string, deserialize_option_number_from_string,
@@ -455,6 +467,8 @@ pub struct Field {
 #[derive(Debug, Deserialize, Clone)]
 #[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
 pub struct Location {
+    /// Only set for the folders of a library section.
+    pub id: Option<u32>,
     pub path: String,
 }
 
@@ -717,6 +731,8 @@ pub struct Metadata {
     pub index: Option<u32>,
     #[serde(rename = "playlistItemID")]
     pub playlist_item_id: Option<u32>,
+    #[serde(rename = "playQueueItemID")]
+    pub play_queue_item_id: Option<u64>,
     #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
     pub child_count: Option<u32>,
     pub season_count: Option<u32>,
@@ -836,6 +852,35 @@ pub struct Metadata {
     pub collection_sort: Option<String>,
     #[serde(default, deserialize_with = "optional_boolish")]
     pub skip_parent: Option<bool>,
+
+    // Only included in search results.
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub score: Option<f32>,
+    pub reason: Option<String>,
+    #[serde(rename = "reasonID")]
+    pub reason_id: Option<u32>,
+    pub reason_title: Option<String>,
+
+    // Only included in the list of playback sessions.
+    #[serde(default, deserialize_with = "deserialize_option_string_from_number")]
+    pub session_key: Option<String>,
+    #[serde(rename = "User")]
+    pub user: Option<User>,
+    #[serde(rename = "Player")]
+    pub player: Option<Box<Player>>,
+    #[serde(rename = "Session")]
+    pub session: Option<Session>,
+    #[serde(rename = "TranscodeSession")]
+    pub transcode_session: Option<Box<TranscodeSessionStats>>,
+
+    // Only included in the watch history.
+    pub history_key: Option<String>,
+    #[serde(default, with = "time::serde::timestamp::option")]
+    pub viewed_at: Option<OffsetDateTime>,
+    #[serde(rename = "accountID")]
+    pub account_id: Option<u64>,
+    #[serde(rename = "deviceID")]
+    pub device_id: Option<u64>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -1114,7 +1159,7 @@ impl TryFrom<Value> for ContentDirectory {
     }
 }
 
-#[derive(Debug, Deserialize_repr, Clone, Copy, Serialize_repr)]
+#[derive(Debug, Deserialize_repr, Clone, Copy, Serialize_repr, PartialEq, Eq)]
 #[repr(u16)]
 pub enum SearchType {
     Movie = 1,
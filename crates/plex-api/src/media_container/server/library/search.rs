@@ -0,0 +1,107 @@
+use crate::media_container::{helpers::optional_boolish, MediaContainer};
+use serde::{Deserialize, Serialize};
+use serde_plain::{derive_display_from_serialize, derive_fromstr_from_deserialize};
+use time::OffsetDateTime;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterType {
+    String,
+    Integer,
+    Boolean,
+    Date,
+    #[cfg(not(feature = "tests_deny_unknown_fields"))]
+    #[serde(other)]
+    Unknown,
+}
+
+derive_fromstr_from_deserialize!(FilterType);
+
+/// A filter supported by a library section, as returned by
+/// `/library/sections/{id}/filters`.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryFilter {
+    /// The name of the query parameter used for the filter, e.g. `genre`.
+    pub filter: String,
+    pub filter_type: FilterType,
+    /// The path listing all the possible values of the filter.
+    pub key: String,
+    pub title: String,
+    #[serde(rename = "type")]
+    pub directory_type: String,
+    #[serde(default, deserialize_with = "optional_boolish")]
+    pub advanced: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+derive_fromstr_from_deserialize!(SortDirection);
+derive_display_from_serialize!(SortDirection);
+
+/// A sort order supported by a library section, as returned by
+/// `/library/sections/{id}/sorts`.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct LibrarySort {
+    /// The name of the field used for sorting, e.g. `titleSort`.
+    pub key: String,
+    pub title: String,
+    /// Present only on the sort order that is currently the default one.
+    pub default: Option<SortDirection>,
+    pub default_direction: SortDirection,
+    pub desc_key: Option<String>,
+    pub first_character_key: Option<String>,
+    pub active_direction: Option<SortDirection>,
+    #[serde(rename = "type")]
+    pub directory_type: Option<String>,
+}
+
+/// One of the possible values of a library filter.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryFilterValue {
+    /// The value that should be passed to the filter.
+    pub key: String,
+    pub title: String,
+    pub fast_key: Option<String>,
+    #[serde(rename = "type")]
+    pub value_type: Option<String>,
+    pub thumb: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryDirectoryMediaContainer<T> {
+    #[serde(default, deserialize_with = "optional_boolish")]
+    pub allow_sync: Option<bool>,
+    pub art: Option<String>,
+    pub thumb: Option<String>,
+    pub content: Option<String>,
+    pub title1: Option<String>,
+    pub title2: Option<String>,
+    pub view_group: Option<String>,
+    pub view_mode: Option<u32>,
+    #[serde(rename = "librarySectionID")]
+    pub library_section_id: Option<u32>,
+    pub media_tag_prefix: Option<String>,
+    #[serde(default, with = "time::serde::timestamp::option")]
+    pub media_tag_version: Option<OffsetDateTime>,
+    #[serde(rename = "nocache")]
+    pub no_cache: Option<bool>,
+
+    #[serde(flatten)]
+    pub media_container: MediaContainer,
+
+    #[serde(default = "Vec::new", rename = "Directory")]
+    pub directories: Vec<T>,
+}
@@ -0,0 +1,102 @@
+use super::{LibraryType, Location, SearchType};
+use crate::media_container::{helpers::optional_boolish, MediaContainer};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+/// A library section as returned by `/library/sections`. Unlike the
+/// libraries listed by the media providers this includes the folders the
+/// section is made of.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct LibrarySection {
+    pub key: String,
+    #[serde(rename = "type")]
+    pub library_type: LibraryType,
+    pub title: String,
+    pub agent: String,
+    pub scanner: String,
+    pub language: String,
+    pub uuid: String,
+    pub allow_sync: Option<bool>,
+    pub art: Option<String>,
+    pub composite: Option<String>,
+    pub thumb: Option<String>,
+    pub filters: Option<bool>,
+    pub refreshing: Option<bool>,
+    pub content: Option<bool>,
+    pub directory: Option<bool>,
+    #[serde(default, deserialize_with = "optional_boolish")]
+    pub hidden: Option<bool>,
+    pub content_changed_at: Option<u64>,
+    #[serde(default, with = "time::serde::timestamp::option")]
+    pub created_at: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::timestamp::option")]
+    pub updated_at: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::timestamp::option")]
+    pub scanned_at: Option<OffsetDateTime>,
+    #[serde(default, rename = "Location")]
+    pub locations: Vec<Location>,
+}
+
+/// A metadata agent installed on the server, e.g. `tv.plex.agents.movie`.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct Agent {
+    pub identifier: String,
+    pub name: String,
+    pub has_prefs: bool,
+    pub has_attribution: bool,
+    pub primary: Option<bool>,
+    #[serde(default, rename = "MediaType")]
+    pub media_types: Vec<AgentMediaType>,
+}
+
+/// The type of items an agent can provide metadata for.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct AgentMediaType {
+    pub media_type: SearchType,
+    pub name: Option<String>,
+    #[serde(default, rename = "Language")]
+    pub languages: Vec<AgentLanguage>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub struct AgentLanguage {
+    /// The code used when creating a library, e.g. `en-US`.
+    pub code: String,
+    pub title: String,
+}
+
+/// A scanner used to find the media files of a library, e.g. `Plex Movie`.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub struct Scanner {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub scanner_type: Option<SearchType>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub struct AgentsMediaContainer {
+    #[serde(flatten)]
+    pub media_container: MediaContainer,
+
+    #[serde(default, rename = "Agent")]
+    pub agents: Vec<Agent>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+pub struct ScannersMediaContainer {
+    #[serde(flatten)]
+    pub media_container: MediaContainer,
+
+    #[serde(default, rename = "Scanner")]
+    pub scanners: Vec<Scanner>,
+}
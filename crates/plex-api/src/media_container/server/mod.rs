@@ -1,5 +1,11 @@
+pub mod activity;
+pub mod butler;
 mod feature;
+pub mod hub;
 pub mod library;
+pub mod notification;
+pub mod play_queue;
+pub mod session;
 
 pub use self::feature::Feature;
 use self::library::ContentDirectory;
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use super::{activity::Activity, session::PlaybackState};
+use crate::{
+    media_container::{helpers::deserialize_option_string_from_number, preferences::Setting},
+    transcode::TranscodeSessionStats,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_aux::prelude::{deserialize_number_from_string, deserialize_option_number_from_string};
+use serde_json::Value;
+use serde_plain::derive_fromstr_from_deserialize;
+use time::OffsetDateTime;
+
+/// Playback state change reported by one of the players.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct PlaySessionStateNotification {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub session_key: u64,
+    pub client_identifier: String,
+    pub guid: String,
+    pub rating_key: String,
+    pub url: String,
+    pub key: String,
+    pub view_offset: u64,
+    #[serde(rename = "playQueueID")]
+    pub play_queue_id: Option<u64>,
+    #[serde(rename = "playQueueItemID")]
+    pub play_queue_item_id: Option<u64>,
+    pub state: PlaybackState,
+    pub transcode_session: Option<String>,
+}
+
+/// A change to one of the items in a library.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEntry {
+    pub identifier: String,
+    #[serde(
+        default,
+        rename = "sectionID",
+        deserialize_with = "deserialize_option_string_from_number"
+    )]
+    pub section_id: Option<String>,
+    #[serde(
+        default,
+        rename = "itemID",
+        deserialize_with = "deserialize_option_string_from_number"
+    )]
+    pub item_id: Option<String>,
+    #[serde(
+        default,
+        rename = "parentItemID",
+        deserialize_with = "deserialize_option_string_from_number"
+    )]
+    pub parent_item_id: Option<String>,
+    #[serde(
+        default,
+        rename = "rootItemID",
+        deserialize_with = "deserialize_option_string_from_number"
+    )]
+    pub root_item_id: Option<String>,
+    /// The metadata type of the item, see
+    /// [`SearchType`](super::library::SearchType).
+    #[serde(rename = "type")]
+    pub item_type: i32,
+    pub title: String,
+    /// The state of the item: 0 - created, 5 - processed, 9 - deleted.
+    /// Other values are used while the item is being processed.
+    pub state: i32,
+    pub metadata_state: Option<String>,
+    pub media_state: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub queue_size: Option<u32>,
+    #[serde(default, with = "time::serde::timestamp::option")]
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityEvent {
+    Started,
+    Updated,
+    Ended,
+    #[cfg(not(feature = "tests_deny_unknown_fields"))]
+    #[serde(other)]
+    Unknown,
+}
+
+derive_fromstr_from_deserialize!(ActivityEvent);
+
+/// Progress of one of the long-running server activities.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityNotification {
+    pub event: ActivityEvent,
+    pub uuid: String,
+    #[serde(rename = "Activity")]
+    pub activity: Activity,
+}
+
+/// A message the server wants to show to the users.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct StatusNotification {
+    pub title: String,
+    pub description: Option<String>,
+    pub notification_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressNotification {
+    pub message: String,
+}
+
+/// Only the key is reported once a transcode session is finished.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct TranscodeSessionKey {
+    key: String,
+}
+
+/// An event sent by the server.
+#[derive(Debug, Clone)]
+pub enum Notification {
+    Playing(PlaySessionStateNotification),
+    Timeline(TimelineEntry),
+    Activity(ActivityNotification),
+    TranscodeSessionStart(Box<TranscodeSessionStats>),
+    TranscodeSessionUpdate(Box<TranscodeSessionStats>),
+    /// A transcode session with the key has finished.
+    TranscodeSessionEnd(String),
+    Preference(Setting),
+    Status(StatusNotification),
+    Progress(ProgressNotification),
+    /// An event this crate doesn't know about yet, with its raw payload.
+    Unknown {
+        event: String,
+        data: Value,
+    },
+}
+
+/// Extracts the payload from the event data, which has the form of
+/// `{"{name}": {payload}}`.
+fn payload<T: DeserializeOwned>(data: &str, name: &'static str) -> serde_json::Result<T> {
+    let mut data: HashMap<String, Value> = serde_json::from_str(data)?;
+    let payload = data
+        .remove(name)
+        .ok_or_else(|| serde::de::Error::missing_field(name))?;
+    serde_json::from_value(payload)
+}
+
+impl Notification {
+    /// Decodes the notification from a server sent event. Returns `None` for
+    /// the keep-alive events.
+    pub(crate) fn from_event(event: &str, data: &str) -> serde_json::Result<Option<Self>> {
+        let notification = match event {
+            "ping" => return Ok(None),
+            "playing" => Self::Playing(payload(data, "PlaySessionStateNotification")?),
+            "timeline" => Self::Timeline(payload(data, "TimelineEntry")?),
+            "activity" => Self::Activity(payload(data, "ActivityNotification")?),
+            "transcodeSession.start" => {
+                Self::TranscodeSessionStart(payload(data, "TranscodeSession")?)
+            }
+            "transcodeSession.update" => {
+                Self::TranscodeSessionUpdate(payload(data, "TranscodeSession")?)
+            }
+            "transcodeSession.end" => Self::TranscodeSessionEnd(
+                payload::<TranscodeSessionKey>(data, "TranscodeSession")?.key,
+            ),
+            "preference" => Self::Preference(payload(data, "Setting")?),
+            "status" => Self::Status(payload(data, "StatusNotification")?),
+            "progress" => Self::Progress(payload(data, "ProgressNotification")?),
+            _ => Self::Unknown {
+                event: event.to_owned(),
+                data: serde_json::from_str(data).unwrap_or_else(|_| Value::String(data.to_owned())),
+            },
+        };
+
+        Ok(Some(notification))
+    }
+}
@@ -0,0 +1,43 @@
+use super::library::Metadata;
+use crate::media_container::{helpers::deserialize_option_string_from_number, MediaContainer};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct PlayQueueMediaContainer {
+    #[serde(rename = "playQueueID")]
+    pub play_queue_id: u64,
+    #[serde(rename = "playQueueSelectedItemID")]
+    pub play_queue_selected_item_id: Option<u64>,
+    /// The position of the selected item within the whole play queue.
+    pub play_queue_selected_item_offset: Option<u32>,
+    #[serde(
+        default,
+        rename = "playQueueSelectedMetadataItemID",
+        deserialize_with = "deserialize_option_string_from_number"
+    )]
+    pub play_queue_selected_metadata_item_id: Option<String>,
+    pub play_queue_shuffled: bool,
+    #[serde(rename = "playQueueSourceURI")]
+    pub play_queue_source_uri: Option<String>,
+    pub play_queue_total_count: u32,
+    /// Increased by the server every time the play queue changes.
+    pub play_queue_version: u32,
+    #[serde(rename = "playQueueLastAddedItemID")]
+    pub play_queue_last_added_item_id: Option<u64>,
+    #[serde(rename = "playQueuePlaylistID")]
+    pub play_queue_playlist_id: Option<u64>,
+    pub play_queue_playlist_title: Option<String>,
+    pub allow_sync: Option<bool>,
+    pub media_tag_prefix: Option<String>,
+    #[serde(default, with = "time::serde::timestamp::option")]
+    pub media_tag_version: Option<OffsetDateTime>,
+
+    #[serde(flatten)]
+    pub media_container: MediaContainer,
+
+    #[serde(default, rename = "Metadata")]
+    pub metadata: Vec<Metadata>,
+}
@@ -0,0 +1,68 @@
+pub use crate::media_container::player::PlaybackState;
+
+use crate::media_container::helpers::deserialize_option_string_from_number;
+use serde::Deserialize;
+use serde_aux::prelude::deserialize_number_from_string;
+use serde_plain::derive_fromstr_from_deserialize;
+
+/// The user playing the item in a playback session.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub id: u64,
+    pub title: String,
+    pub thumb: Option<String>,
+}
+
+/// The device playing the item in a playback session.
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct Player {
+    pub address: String,
+    pub machine_identifier: String,
+    pub title: String,
+    pub device: Option<String>,
+    pub model: Option<String>,
+    pub platform: Option<String>,
+    pub platform_version: Option<String>,
+    pub product: Option<String>,
+    pub profile: Option<String>,
+    pub vendor: Option<String>,
+    pub version: Option<String>,
+    pub remote_public_address: Option<String>,
+    pub state: PlaybackState,
+    #[serde(rename = "userID")]
+    pub user_id: Option<u64>,
+    #[serde(default)]
+    pub local: bool,
+    #[serde(default)]
+    pub relayed: bool,
+    #[serde(default)]
+    pub secure: bool,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionLocation {
+    Lan,
+    Wan,
+    #[cfg(not(feature = "tests_deny_unknown_fields"))]
+    #[serde(other)]
+    Unknown,
+}
+
+derive_fromstr_from_deserialize!(SessionLocation);
+
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "tests_deny_unknown_fields", serde(deny_unknown_fields))]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    #[serde(default, deserialize_with = "deserialize_option_string_from_number")]
+    pub id: Option<String>,
+    /// The bandwidth used by the session in kbps.
+    pub bandwidth: Option<u64>,
+    pub location: Option<SessionLocation>,
+}
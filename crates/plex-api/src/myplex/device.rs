@@ -1,13 +1,127 @@
 use crate::{
-    http_client::HttpClient,
-    media_container::devices::{DevicesMediaContainer, Feature},
-    url::{MYPLEX_DEVICES, MYPLEX_RESOURCES},
+    http_client::{Failover, HttpClient},
+    media_container::devices::{
+        DevicesMediaContainer, Feature, Resource, ResourceConnection, SyncItem,
+        SyncItemsMediaContainer, SyncList,
+    },
+    url::{MYPLEX_DEVICE, MYPLEX_DEVICES, MYPLEX_DEVICE_SYNC_ITEMS, MYPLEX_RESOURCES},
     Error, Player, Result, Server,
 };
-use futures::{future::select_ok, FutureExt};
+use futures::{future::select_ok, stream::FuturesUnordered, FutureExt, StreamExt};
+use futures_timer::Delay;
+use http::Uri;
 use secrecy::ExposeSecret;
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+use time::OffsetDateTime;
 use tracing::{debug, error, trace};
 
+/// How long the connection that worked last time is tried alone before the
+/// other connections of the same kind.
+const CACHED_CONNECTION_HEAD_START: Duration = Duration::from_millis(250);
+
+/// The connection that last worked for each device, by machine identifier.
+static CONNECTION_CACHE: LazyLock<Mutex<HashMap<String, Uri>>> = LazyLock::new(Default::default);
+
+fn remember_connection(machine_identifier: &str, uri: &Uri) {
+    if let Ok(mut cache) = CONNECTION_CACHE.lock() {
+        cache.insert(machine_identifier.to_owned(), uri.clone());
+    }
+}
+
+fn cached_connection(machine_identifier: &str) -> Option<Uri> {
+    CONNECTION_CACHE
+        .lock()
+        .ok()?
+        .get(machine_identifier)
+        .cloned()
+}
+
+/// Forgets the connections that worked before, so that the next
+/// [`Device::connect`] tries all of them again.
+pub fn clear_connection_cache() {
+    if let Ok(mut cache) = CONNECTION_CACHE.lock() {
+        cache.clear();
+    }
+}
+
+/// How [`Device::connect_with`] picks one of the device's connections.
+///
+/// Local connections are preferred over remote ones, which are preferred
+/// over relayed ones. The fastest connection to answer is used unless a
+/// preferred one is still being tried. Among connections of the same kind
+/// the one that worked last time gets a head start.
+#[derive(Debug, Clone)]
+pub struct ConnectionStrategy {
+    /// Use relayed connections, which are bandwidth limited, when nothing
+    /// else works.
+    pub allow_relay: bool,
+    /// Prefer `https` connections over `http` ones of the same kind. Devices
+    /// requiring secure connections are never connected to over `http`.
+    pub prefer_https: bool,
+    /// Switch a server to the next best connection when the one in use stops
+    /// answering.
+    pub failover: bool,
+}
+
+impl Default for ConnectionStrategy {
+    fn default() -> Self {
+        Self {
+            allow_relay: true,
+            prefer_https: true,
+            failover: true,
+        }
+    }
+}
+
+impl ConnectionStrategy {
+    /// Lower is better.
+    fn rank(&self, connection: &ResourceConnection) -> u8 {
+        let kind = if connection.relay {
+            2
+        } else if connection.local {
+            0
+        } else {
+            1
+        };
+        let insecure = self.prefer_https && !is_https(connection);
+
+        kind * 2 + u8::from(insecure)
+    }
+}
+
+fn is_https(connection: &ResourceConnection) -> bool {
+    connection.uri.scheme_str() == Some("https")
+}
+
+/// Which devices [`DeviceManager::cleanup`] removes. Devices have to match
+/// all the set conditions, and at least one of `last_seen_before` and
+/// `products` has to be set.
+#[derive(Debug, Clone, Default)]
+pub struct DeviceCleanup {
+    /// Remove devices that weren't seen since then.
+    pub last_seen_before: Option<OffsetDateTime>,
+    /// Remove devices of these products, e.g. `Plex Web`.
+    pub products: Vec<String>,
+    /// Remove the device with the client's own identifier as well.
+    pub force: bool,
+}
+
+impl DeviceCleanup {
+    fn matches(&self, device: &Device<'_>) -> bool {
+        self.last_seen_before
+            .is_none_or(|before| device.last_seen_at() < before)
+            && (self.products.is_empty()
+                || self
+                    .products
+                    .iter()
+                    .any(|product| product == device.product()))
+    }
+}
+
 pub struct DeviceManager {
     pub client: HttpClient,
 }
@@ -17,38 +131,78 @@ impl DeviceManager {
         Self { client }
     }
 
-    async fn devices_internal<'a>(&'a self, url: &str) -> Result<Vec<Device<'a>>> {
+    fn wrap<'a>(&'a self, resources: impl IntoIterator<Item = Resource>) -> Vec<Device<'a>> {
+        resources
+            .into_iter()
+            .map(|resource| Device {
+                inner: resource,
+                client: &self.client,
+            })
+            .collect()
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn devices(&self) -> Result<Vec<Device<'_>>> {
         let container: DevicesMediaContainer = self
             .client
-            .get(url)
+            .get(MYPLEX_DEVICES)
             .header("Accept", "application/xml")
             .xml()
             .await?;
 
-        Ok(container
-            .devices
-            .into_iter()
-            .map(|device| Device {
-                inner: device,
-                client: &self.client,
-            })
-            .collect())
+        Ok(self.wrap(container.devices.into_iter().map(Into::into)))
     }
 
+    /// Lists the servers and players the account has access to, including
+    /// all of their connections.
     #[tracing::instrument(level = "debug", skip(self))]
-    pub async fn devices(&self) -> Result<Vec<Device<'_>>> {
-        self.devices_internal(MYPLEX_DEVICES).await
+    pub async fn resources(&self) -> Result<Vec<Device<'_>>> {
+        let resources: Vec<Resource> = self
+            .client
+            .get(format!(
+                "{MYPLEX_RESOURCES}?includeHttps=1&includeRelay=1&includeIPv6=1"
+            ))
+            .json()
+            .await?;
+
+        Ok(self.wrap(resources))
     }
 
+    /// Removes the devices matching the conditions, returning the removed
+    /// ones' identifiers. At least one condition has to be set. When some
+    /// devices couldn't be removed the others still are, and the error lists
+    /// both.
     #[tracing::instrument(level = "debug", skip(self))]
-    pub async fn resources(&self) -> Result<Vec<Device<'_>>> {
-        self.devices_internal(MYPLEX_RESOURCES).await
+    pub async fn cleanup(&self, cleanup: &DeviceCleanup) -> Result<Vec<String>> {
+        if cleanup.last_seen_before.is_none() && cleanup.products.is_empty() {
+            return Err(Error::DeviceCleanupWithoutConditions);
+        }
+
+        let mut removed = vec![];
+        let mut failed = vec![];
+        for device in self.devices().await? {
+            if !cleanup.matches(&device) || (!cleanup.force && device.is_current()) {
+                continue;
+            }
+
+            let identifier = device.identifier().to_owned();
+            match device.force_remove().await {
+                Ok(()) => removed.push(identifier),
+                Err(error) => failed.push((identifier, error)),
+            }
+        }
+
+        if failed.is_empty() {
+            Ok(removed)
+        } else {
+            Err(Error::DeviceCleanupIncomplete { removed, failed })
+        }
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct Device<'a> {
-    inner: crate::media_container::devices::Device,
+    inner: Resource,
     client: &'a HttpClient,
 }
 
@@ -76,6 +230,129 @@ impl Device<'_> {
         self.provides(Feature::Controller)
     }
 
+    pub fn product(&self) -> &str {
+        &self.inner.product
+    }
+
+    /// Whether the device belongs to the account rather than being shared.
+    pub fn is_owned(&self) -> bool {
+        self.inner.owned
+    }
+
+    /// Whether the device belongs to a member of the Plex Home.
+    pub fn is_home(&self) -> bool {
+        self.inner.home
+    }
+
+    /// Whether the device is currently online.
+    pub fn is_online(&self) -> bool {
+        self.inner.presence
+    }
+
+    /// The name of the account sharing the device.
+    pub fn source_title(&self) -> Option<&str> {
+        self.inner.source_title.as_deref()
+    }
+
+    pub fn connections(&self) -> &[ResourceConnection] {
+        &self.inner.connections
+    }
+
+    pub fn created_at(&self) -> OffsetDateTime {
+        self.inner.created_at
+    }
+
+    pub fn last_seen_at(&self) -> OffsetDateTime {
+        self.inner.last_seen_at
+    }
+
+    /// The sync status of the device. Only available for devices retrieved
+    /// with [`DeviceManager::devices`].
+    pub fn sync_list(&self) -> Option<&SyncList> {
+        self.inner.sync_list.as_ref()
+    }
+
+    /// Whether this is the device the client identifies as.
+    pub fn is_current(&self) -> bool {
+        self.inner.client_identifier == self.client.x_plex_client_identifier
+    }
+
+    fn path(&self) -> Result<String> {
+        let id = self.inner.id.ok_or(Error::DeviceIdNotAvailable)?;
+        Ok(MYPLEX_DEVICE.replace("{id}", &id.to_string()))
+    }
+
+    fn sync_items_path(&self) -> String {
+        MYPLEX_DEVICE_SYNC_ITEMS.replace("{client_identifier}", &self.inner.client_identifier)
+    }
+
+    /// Changes the name of the device. Only available for devices retrieved
+    /// with [`DeviceManager::devices`].
+    #[tracing::instrument(level = "debug", skip(self), fields(device_name = self.inner.name))]
+    pub async fn rename(&mut self, name: &str) -> Result {
+        self.client
+            .put(format!(
+                "{}?{}",
+                self.path()?,
+                serde_urlencoded::to_string([("name", name)])?
+            ))
+            .consume()
+            .await?;
+        name.clone_into(&mut self.inner.name);
+        Ok(())
+    }
+
+    /// Removes the device from the account. Only available for devices
+    /// retrieved with [`DeviceManager::devices`]. The device the client
+    /// identifies as is never removed, use [`Device::force_remove`] for it.
+    #[tracing::instrument(level = "debug", skip_all, fields(device_name = self.inner.name))]
+    pub async fn remove(self) -> Result {
+        if self.is_current() {
+            return Err(Error::CurrentDeviceRemovalRefused);
+        }
+
+        self.force_remove().await
+    }
+
+    /// Removes the device from the account, even if it's the one the client
+    /// identifies as.
+    #[tracing::instrument(level = "debug", skip_all, fields(device_name = self.inner.name))]
+    pub async fn force_remove(self) -> Result {
+        self.client.delete(self.path()?).consume().await
+    }
+
+    /// Lists the items that are synced to the device.
+    #[tracing::instrument(level = "debug", skip(self), fields(device_name = self.inner.name))]
+    pub async fn sync_items(&self) -> Result<Vec<SyncItem>> {
+        let container: SyncItemsMediaContainer = self
+            .client
+            .get(self.sync_items_path())
+            .header("Accept", "application/xml")
+            .xml()
+            .await?;
+
+        Ok(container.items)
+    }
+
+    /// Stops syncing the item to the device.
+    #[tracing::instrument(level = "debug", skip(self, item), fields(device_name = self.inner.name, sync_item.id = item.id))]
+    pub async fn remove_sync_item(&self, item: &SyncItem) -> Result {
+        self.client
+            .delete(format!("{}/{}", self.sync_items_path(), item.id))
+            .consume()
+            .await
+    }
+
+    /// Stops syncing all the items to the device.
+    #[tracing::instrument(level = "debug", skip(self), fields(device_name = self.inner.name))]
+    pub async fn clear_sync_list(&self) -> Result {
+        for item in self.sync_items().await? {
+            self.remove_sync_item(&item).await?;
+        }
+
+        Ok(())
+    }
+
     /// Returns the authentication token that should be used when connecting to the device.
     /// If it's a shared device, the main authentication token will no be accepted.
     pub fn access_token(&self) -> Option<&str> {
@@ -85,70 +362,148 @@ impl Device<'_> {
             .map(|v| v.expose_secret().as_str())
     }
 
-    /// Connect to the device.
-    #[tracing::instrument(level = "debug", skip(self), fields(device_name = self.inner.name))]
+    /// Connect to the device using the default [`ConnectionStrategy`].
     pub async fn connect(&self) -> Result<DeviceConnection> {
+        self.connect_with(&ConnectionStrategy::default()).await
+    }
+
+    /// Connect to the device, picking the connection according to the
+    /// strategy. How long the chosen connection took to answer is available
+    /// from [`DeviceConnection::latency`].
+    #[tracing::instrument(level = "debug", skip(self), fields(device_name = self.inner.name))]
+    pub async fn connect_with(&self, strategy: &ConnectionStrategy) -> Result<DeviceConnection> {
         if !self.is_server() && !self.is_controller() {
             error!("Device must provide Server or Controller");
             return Err(Error::DeviceConnectionNotSupported);
         }
 
-        if !self.inner.connections.is_empty() {
-            let mut client = self.client.clone();
-            if let Some(access_token) = self.inner.access_token.as_ref() {
-                let access_token = access_token.expose_secret();
-                if access_token != client.x_plex_token() {
-                    debug!("Connecting using access token for the device");
-                    client = client.set_x_plex_token(access_token.to_owned());
+        let https_required = self.inner.https_required;
+        let mut candidates = self
+            .inner
+            .connections
+            .iter()
+            .filter(|connection| !https_required || is_https(connection))
+            .filter(|connection| strategy.allow_relay || !connection.relay)
+            .map(|connection| (strategy.rank(connection), &connection.uri))
+            .collect::<Vec<_>>();
+        // The connection that worked last time only breaks ties, a better
+        // kind of connection is still preferred.
+        let cached = cached_connection(&self.inner.client_identifier);
+        candidates.sort_by_key(|(rank, uri)| (*rank, cached.as_ref() != Some(*uri)));
+        if candidates.is_empty() {
+            return Err(Error::DeviceConnectionsIsEmpty);
+        }
+
+        let mut client = self.client.clone();
+        if let Some(access_token) = self.inner.access_token.as_ref() {
+            let access_token = access_token.expose_secret();
+            if access_token != client.x_plex_token() {
+                debug!("Connecting using access token for the device");
+                client = client.set_x_plex_token(access_token.to_owned());
+            }
+        }
+        if !self.is_server() {
+            client
+                .x_plex_target_client_identifier
+                .clone_from(&self.inner.client_identifier);
+        }
+
+        let cached_rank = candidates
+            .iter()
+            .find(|(_, uri)| cached.as_ref() == Some(*uri))
+            .map(|(rank, _)| *rank);
+        let mut attempts = candidates
+            .iter()
+            .enumerate()
+            .map(|(index, (rank, uri))| {
+                let client = client.clone();
+                let delayed = cached_rank == Some(*rank) && cached.as_ref() != Some(*uri);
+                async move {
+                    if delayed {
+                        Delay::new(CACHED_CONNECTION_HEAD_START).await;
+                    }
+                    trace!("Trying {uri}");
+                    let started = Instant::now();
+                    let result = self.connect_to(uri, client).await;
+                    (index, result, started.elapsed())
+                }
+            })
+            .collect::<FuturesUnordered<_>>();
+
+        let mut finished = vec![false; candidates.len()];
+        let mut connections = candidates.iter().map(|_| None).collect::<Vec<_>>();
+        let mut last_error = None;
+        while let Some((index, result, latency)) = attempts.next().await {
+            finished[index] = true;
+            match result {
+                Ok(connection) => {
+                    debug!(uri = %candidates[index].1, ?latency, "Connection answered");
+                    connections[index] = Some((connection, latency));
+                }
+                Err(error) => {
+                    debug!(uri = %candidates[index].1, %error, "Connection failed");
+                    last_error = Some(error);
                 }
             }
 
-            if self.is_server() {
-                trace!(
-                    "Connecting to server {id}",
-                    id = self.inner.client_identifier,
-                );
-                let futures = self
-                    .inner
-                    .connections
-                    .iter()
-                    .map(|connection| {
-                        trace!("Trying {address}", address = connection.uri);
-                        crate::Server::new(&connection.uri, client.clone()).boxed()
-                    })
-                    .collect::<Vec<_>>();
-
-                let (server, _) = select_ok(futures).await?;
-                trace!("Connected via {address}", address = server.client().api_url);
-                Ok(DeviceConnection::Server(Box::new(server)))
-            } else {
-                trace!(
-                    "Connecting to player {id}",
-                    id = self.inner.client_identifier,
-                );
-                client
-                    .x_plex_target_client_identifier
-                    .clone_from(&self.inner.client_identifier);
-
-                let futures = self
-                    .inner
-                    .connections
-                    .iter()
-                    .map(|connection| {
-                        trace!("Trying {address}", address = connection.uri);
-                        crate::Player::new(&connection.uri, client.clone()).boxed()
-                    })
-                    .collect::<Vec<_>>();
-
-                let (player, _) = select_ok(futures).await?;
-                trace!("Connected via {address}", address = player.client().api_url);
-                Ok(DeviceConnection::Player(Box::new(player)))
+            // The first connection to answer wins once every preferred one
+            // has failed.
+            let winner = (0..candidates.len()).find(|&index| {
+                connections[index].is_some()
+                    && (0..index)
+                        .all(|other| finished[other] || candidates[other].0 == candidates[index].0)
+            });
+            if let Some(index) = winner {
+                let (connection, latency) = connections[index].take().unwrap();
+                return Ok(self.connected(connection, latency, &candidates, index, strategy));
             }
+        }
+
+        Err(last_error.unwrap_or(Error::DeviceConnectionsIsEmpty))
+    }
+
+    async fn connect_to(&self, uri: &Uri, client: HttpClient) -> Result<DeviceConnection> {
+        if self.is_server() {
+            Ok(DeviceConnection::Server(Box::new(
+                Server::new(uri, client).await?,
+            )))
         } else {
-            Err(Error::DeviceConnectionsIsEmpty)
+            Ok(DeviceConnection::Player(Box::new(
+                Player::new(uri, client).await?,
+            )))
         }
     }
 
+    fn connected(
+        &self,
+        mut connection: DeviceConnection,
+        latency: Duration,
+        candidates: &[(u8, &Uri)],
+        index: usize,
+        strategy: &ConnectionStrategy,
+    ) -> DeviceConnection {
+        let uri = candidates[index].1;
+        trace!("Connected via {uri}");
+        remember_connection(&self.inner.client_identifier, uri);
+
+        match connection {
+            DeviceConnection::Server(ref mut server) => {
+                server.set_latency(latency);
+                if strategy.failover && candidates.len() > 1 {
+                    let client_identifier = self.inner.client_identifier.clone();
+                    server.set_failover(Failover::new(
+                        candidates.iter().map(|(_, uri)| (*uri).clone()).collect(),
+                        index,
+                        move |uri| remember_connection(&client_identifier, uri),
+                    ));
+                }
+            }
+            DeviceConnection::Player(ref mut player) => player.set_latency(latency),
+        }
+
+        connection
+    }
+
     /// Establish a connection to the device using server as a proxy.
     #[tracing::instrument(level = "debug", skip(self, server), fields(device_name = self.inner.name))]
     pub async fn connect_via(&self, server: &Server) -> Result<DeviceConnection> {
@@ -177,3 +532,13 @@ pub enum DeviceConnection {
     Server(Box<Server>),
     Player(Box<Player>),
 }
+
+impl DeviceConnection {
+    /// How long the device took to answer when the connection was chosen.
+    pub fn latency(&self) -> Option<Duration> {
+        match self {
+            DeviceConnection::Server(server) => server.latency(),
+            DeviceConnection::Player(player) => player.latency(),
+        }
+    }
+}
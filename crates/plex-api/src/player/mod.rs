@@ -1,17 +1,74 @@
+mod timeline;
+
+pub use self::timeline::TimelineSubscription;
+pub use crate::media_container::player::PlaybackType;
 use crate::{
-    media_container::player::ResourcesMediaContainer,
-    url::{CLIENT_RESOURCES, SERVER_SYSTEM_PROXY},
-    HttpClient, HttpClientBuilder, MyPlex, Result, Server,
+    http_client::RequestBuilder,
+    library::MediaItem,
+    media_container::{
+        player::{ProtocolCapability, ResourcesMediaContainer, TimelineMediaContainer},
+        server::library::MetadataType,
+    },
+    play_queue::PlayQueue,
+    url::{
+        CLIENT_MIRROR, CLIENT_NAVIGATION, CLIENT_PLAYBACK, CLIENT_RESOURCES, CLIENT_TIMELINE,
+        SERVER_PLAY_QUEUES, SERVER_SYSTEM_PROXY,
+    },
+    Error, HttpClient, HttpClientBuilder, MyPlex, Result, Server,
 };
 use http::{uri::PathAndQuery, Uri};
-use std::fmt::{Debug, Display};
+use serde::{Deserialize, Serialize};
+use serde_plain::{derive_display_from_serialize, derive_fromstr_from_deserialize};
+use std::{
+    fmt::{Debug, Display},
+    net::TcpListener,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+impl PlaybackType {
+    fn of<I: MediaItem>(item: &I) -> Self {
+        match item.metadata().metadata_type {
+            Some(MetadataType::Track) => Self::Music,
+            Some(MetadataType::Photo) => Self::Photo,
+            _ => Self::Video,
+        }
+    }
+}
+
+/// Moves around the player's user interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NavigationCommand {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    PageUp,
+    PageDown,
+    Select,
+    Back,
+    Home,
+    ContextMenu,
+    #[serde(rename = "toggleOSD")]
+    ToggleOsd,
+}
+
+derive_fromstr_from_deserialize!(NavigationCommand);
+derive_display_from_serialize!(NavigationCommand);
 
 #[derive(Debug, Clone)]
 pub struct Player {
     client: HttpClient,
-    _media_container: ResourcesMediaContainer,
-    _last_command_id: u64,
+    media_container: ResourcesMediaContainer,
+    /// The player's address when commands are sent through a server.
+    proxy_base_url: Option<String>,
+    last_command_id: Arc<AtomicU64>,
     pub myplex_api_url: Uri,
+    latency: Option<Duration>,
 }
 
 impl Player {
@@ -23,15 +80,18 @@ impl Player {
         <Uri as TryFrom<U>>::Error: Into<http::Error>,
     {
         let myplex_api_url = client.api_url.clone();
+        let client = HttpClientBuilder::from(client).set_api_url(url).build()?;
         Ok(Self {
-            _media_container: client
+            media_container: client
                 .get(CLIENT_RESOURCES)
                 .header("Accept", "application/xml")
                 .xml()
                 .await?,
             client,
+            proxy_base_url: None,
+            last_command_id: Arc::new(AtomicU64::new(0)),
             myplex_api_url,
-            _last_command_id: 0,
+            latency: None,
         })
     }
 
@@ -51,6 +111,11 @@ impl Player {
             .into_parts();
         uri_parts.path_and_query = Some(path_and_query);
         let uri = Uri::from_parts(uri_parts).map_err(Into::<http::Error>::into)?;
+        let proxy_base_url = format!(
+            "{}://{}",
+            uri.scheme_str().unwrap_or("http"),
+            uri.authority().map(|a| a.as_str()).unwrap_or_default()
+        );
 
         let media_container: ResourcesMediaContainer = client
             .get(SERVER_SYSTEM_PROXY)
@@ -61,10 +126,12 @@ impl Player {
             .x_plex_target_client_identifier
             .clone_from(&media_container.player.machine_identifier);
         Ok(Self {
-            _media_container: media_container,
+            media_container,
             client,
+            proxy_base_url: Some(proxy_base_url),
+            last_command_id: Arc::new(AtomicU64::new(0)),
             myplex_api_url: server.myplex_api_url.clone(),
-            _last_command_id: 0,
+            latency: None,
         })
     }
 
@@ -87,4 +154,272 @@ impl Player {
     pub fn client(&self) -> &HttpClient {
         &self.client
     }
+
+    /// How long the player took to answer when it was connected to with
+    /// [`Device::connect`](crate::device::Device::connect).
+    pub fn latency(&self) -> Option<Duration> {
+        self.latency
+    }
+
+    pub(crate) fn set_latency(&mut self, latency: Duration) {
+        self.latency = Some(latency);
+    }
+
+    pub fn machine_identifier(&self) -> &str {
+        &self.media_container.player.machine_identifier
+    }
+
+    pub fn title(&self) -> &str {
+        &self.media_container.player.title
+    }
+
+    /// The commands the player accepts, see [`ProtocolCapability`].
+    pub fn capabilities(&self) -> &[ProtocolCapability] {
+        &self.media_container.player.protocol_capabilities
+    }
+
+    pub fn supports(&self, capability: ProtocolCapability) -> bool {
+        self.capabilities().contains(&capability)
+    }
+
+    /// Builds a request for a command to the player. Every command is
+    /// numbered, so that the player can ignore any that arrive out of order.
+    fn command_request(
+        &self,
+        capability: ProtocolCapability,
+        path: &str,
+        mut params: Vec<(&str, String)>,
+    ) -> Result<RequestBuilder<'_, String>> {
+        if !self.supports(capability) {
+            return Err(Error::PlayerCapabilityNotSupported(capability));
+        }
+
+        let command_id = self.last_command_id.fetch_add(1, Ordering::SeqCst) + 1;
+        params.push(("commandID", command_id.to_string()));
+        let path = format!("{path}?{}", serde_urlencoded::to_string(params)?);
+
+        Ok(match self.proxy_base_url {
+            Some(ref base_url) => self
+                .client
+                .get(SERVER_SYSTEM_PROXY.to_owned())
+                .header("X-Plex-Url", format!("{base_url}{path}")),
+            None => self.client.get(path),
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip(self, params))]
+    async fn command(
+        &self,
+        capability: ProtocolCapability,
+        path: &str,
+        params: Vec<(&str, String)>,
+    ) -> Result {
+        self.command_request(capability, path, params)?
+            .consume()
+            .await
+    }
+
+    /// Waits for the player's timelines to change and returns them. The
+    /// first call returns immediately.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn timeline(&self) -> Result<TimelineMediaContainer> {
+        self.command_request(
+            ProtocolCapability::Timeline,
+            &format!("{CLIENT_TIMELINE}/poll"),
+            vec![("wait", "1".to_owned())],
+        )?
+        .timeout(None)
+        .xml()
+        .await
+    }
+
+    /// Asks the player to push its timelines to the listener, see
+    /// [`TimelineSubscription`].
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn subscribe(&self, listener: TcpListener) -> Result<TimelineSubscription> {
+        self.subscribe_port(listener.local_addr()?.port()).await?;
+        TimelineSubscription::new(self.clone(), listener)
+    }
+
+    async fn subscribe_port(&self, port: u16) -> Result {
+        self.command(
+            ProtocolCapability::Timeline,
+            &format!("{CLIENT_TIMELINE}/subscribe"),
+            vec![("port", port.to_string()), ("protocol", "http".to_owned())],
+        )
+        .await
+    }
+
+    async fn unsubscribe(&self) -> Result {
+        self.command(
+            ProtocolCapability::Timeline,
+            &format!("{CLIENT_TIMELINE}/unsubscribe"),
+            vec![],
+        )
+        .await
+    }
+
+    /// Describes the server the player should retrieve the item from.
+    fn server_params(server: &Server) -> Vec<(&'static str, String)> {
+        let api_url = server.client().base_url();
+        let mut params = vec![
+            ("machineIdentifier", server.machine_identifier().to_owned()),
+            ("address", api_url.host().unwrap_or_default().to_owned()),
+            ("port", api_url.port_u16().unwrap_or(32400).to_string()),
+            (
+                "protocol",
+                api_url.scheme_str().unwrap_or("http").to_owned(),
+            ),
+        ];
+
+        let token = server.client().x_plex_token();
+        if !token.is_empty() {
+            params.push(("token", token.to_owned()));
+        }
+
+        params
+    }
+
+    /// Starts playing the item from the server at the offset in
+    /// milliseconds. If a play queue is given the player continues with the
+    /// rest of the queue afterwards.
+    #[tracing::instrument(level = "debug", skip_all, fields(item.id = item.rating_key()))]
+    pub async fn play_media<I: MediaItem>(
+        &self,
+        server: &Server,
+        item: &I,
+        play_queue: Option<&PlayQueue>,
+        offset: u64,
+    ) -> Result {
+        let mut params = Self::server_params(server);
+        params.extend([
+            ("key", item.metadata().key.clone()),
+            ("offset", offset.to_string()),
+            (
+                "providerIdentifier",
+                "com.plexapp.plugins.library".to_owned(),
+            ),
+            ("type", PlaybackType::of(item).to_string()),
+        ]);
+
+        if let Some(play_queue) = play_queue {
+            if !self.supports(ProtocolCapability::PlayQueues) {
+                return Err(Error::PlayerCapabilityNotSupported(
+                    ProtocolCapability::PlayQueues,
+                ));
+            }
+            params.push((
+                "containerKey",
+                format!("{SERVER_PLAY_QUEUES}/{}?own=1", play_queue.id()),
+            ));
+        }
+
+        self.command(
+            ProtocolCapability::Playback,
+            &format!("{CLIENT_PLAYBACK}/playMedia"),
+            params,
+        )
+        .await
+    }
+
+    async fn playback(&self, command: &str, playback_type: PlaybackType) -> Result {
+        self.command(
+            ProtocolCapability::Playback,
+            &format!("{CLIENT_PLAYBACK}/{command}"),
+            vec![("type", playback_type.to_string())],
+        )
+        .await
+    }
+
+    pub async fn play(&self, playback_type: PlaybackType) -> Result {
+        self.playback("play", playback_type).await
+    }
+
+    pub async fn pause(&self, playback_type: PlaybackType) -> Result {
+        self.playback("pause", playback_type).await
+    }
+
+    pub async fn stop(&self, playback_type: PlaybackType) -> Result {
+        self.playback("stop", playback_type).await
+    }
+
+    pub async fn skip_next(&self, playback_type: PlaybackType) -> Result {
+        self.playback("skipNext", playback_type).await
+    }
+
+    pub async fn skip_previous(&self, playback_type: PlaybackType) -> Result {
+        self.playback("skipPrevious", playback_type).await
+    }
+
+    /// Jumps to the offset in milliseconds.
+    pub async fn seek_to(&self, playback_type: PlaybackType, offset: u64) -> Result {
+        self.command(
+            ProtocolCapability::Playback,
+            &format!("{CLIENT_PLAYBACK}/seekTo"),
+            vec![
+                ("type", playback_type.to_string()),
+                ("offset", offset.to_string()),
+            ],
+        )
+        .await
+    }
+
+    /// Sets the volume, between 0 and 100.
+    pub async fn set_volume(&self, playback_type: PlaybackType, volume: u8) -> Result {
+        self.command(
+            ProtocolCapability::Playback,
+            &format!("{CLIENT_PLAYBACK}/setParameters"),
+            vec![
+                ("type", playback_type.to_string()),
+                ("volume", volume.min(100).to_string()),
+            ],
+        )
+        .await
+    }
+
+    /// Switches to the audio and subtitle streams with the given ids. A
+    /// subtitle stream id of 0 turns subtitles off.
+    pub async fn set_streams(
+        &self,
+        playback_type: PlaybackType,
+        audio_stream_id: Option<u64>,
+        subtitle_stream_id: Option<u64>,
+    ) -> Result {
+        let mut params = vec![("type", playback_type.to_string())];
+        if let Some(id) = audio_stream_id {
+            params.push(("audioStreamID", id.to_string()));
+        }
+        if let Some(id) = subtitle_stream_id {
+            params.push(("subtitleStreamID", id.to_string()));
+        }
+
+        self.command(
+            ProtocolCapability::Playback,
+            &format!("{CLIENT_PLAYBACK}/setStreams"),
+            params,
+        )
+        .await
+    }
+
+    pub async fn navigate(&self, command: NavigationCommand) -> Result {
+        self.command(
+            ProtocolCapability::Navigation,
+            &format!("{CLIENT_NAVIGATION}/{command}"),
+            vec![],
+        )
+        .await
+    }
+
+    /// Shows the details of the item from the server without playing it.
+    #[tracing::instrument(level = "debug", skip_all, fields(item.id = item.rating_key()))]
+    pub async fn mirror<I: MediaItem>(&self, server: &Server, item: &I) -> Result {
+        let mut params = Self::server_params(server);
+        params.extend([
+            ("key", item.metadata().key.clone()),
+            ("type", PlaybackType::of(item).to_string()),
+        ]);
+
+        self.command(ProtocolCapability::Mirror, CLIENT_MIRROR, params)
+            .await
+    }
 }
@@ -0,0 +1,125 @@
+use std::{
+    io,
+    net::{SocketAddr, TcpListener, TcpStream},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    thread,
+};
+
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    Stream,
+};
+
+use crate::{
+    http_server::{read_request, wake_listener, write_response},
+    media_container::player::TimelineMediaContainer,
+    Result,
+};
+
+use super::Player;
+
+/// The largest timeline body accepted from a player.
+const MAX_TIMELINE_SIZE: usize = 256 * 1024;
+
+/// A stream of the timelines a player pushes to a local HTTP endpoint.
+///
+/// Players forget subscribers that haven't renewed their subscription for 90
+/// seconds, so [`renew`](TimelineSubscription::renew) should be called about
+/// once a minute. The endpoint is closed when the subscription is dropped.
+#[derive(Debug)]
+pub struct TimelineSubscription {
+    player: Player,
+    local_addr: SocketAddr,
+    receiver: UnboundedReceiver<Result<TimelineMediaContainer>>,
+    closed: Arc<AtomicBool>,
+}
+
+impl TimelineSubscription {
+    pub(super) fn new(player: Player, listener: TcpListener) -> Result<Self> {
+        let local_addr = listener.local_addr()?;
+        let closed = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = unbounded();
+
+        let listener_closed = closed.clone();
+        thread::Builder::new()
+            .name("plex-timeline-listener".to_owned())
+            .spawn(move || listen(listener, sender, listener_closed))?;
+
+        Ok(Self {
+            player,
+            local_addr,
+            receiver,
+            closed,
+        })
+    }
+
+    /// Renews the subscription so that the player keeps pushing timelines.
+    pub async fn renew(&self) -> Result {
+        self.player.subscribe_port(self.local_addr.port()).await
+    }
+
+    /// Asks the player to stop pushing timelines and closes the endpoint.
+    pub async fn unsubscribe(self) -> Result {
+        self.player.unsubscribe().await
+    }
+}
+
+impl Stream for TimelineSubscription {
+    type Item = Result<TimelineMediaContainer>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+impl Drop for TimelineSubscription {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::SeqCst);
+        wake_listener(self.local_addr);
+    }
+}
+
+fn listen(
+    listener: TcpListener,
+    sender: UnboundedSender<Result<TimelineMediaContainer>>,
+    closed: Arc<AtomicBool>,
+) {
+    for stream in listener.incoming() {
+        if closed.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let result = stream
+            .map_err(Into::into)
+            .and_then(|stream| read_timeline(&stream));
+        if let Err(error) = &result {
+            tracing::warn!(%error, "Failed to receive a timeline");
+        }
+        if sender.unbounded_send(result).is_err() {
+            break;
+        }
+    }
+}
+
+/// Reads the timelines from a single HTTP request.
+fn read_timeline(stream: &TcpStream) -> Result<TimelineMediaContainer> {
+    let request = match read_request(stream, MAX_TIMELINE_SIZE) {
+        Ok(request) => request,
+        Err(error) => {
+            if let Some(status) = error.status() {
+                let _ = write_response(stream, status, &[], "");
+            }
+            return Err(io::Error::from(error).into());
+        }
+    };
+    write_response(stream, 200, &[], "")?;
+
+    Ok(quick_xml::de::from_str(&String::from_utf8_lossy(
+        &request.body,
+    ))?)
+}
@@ -0,0 +1,640 @@
+//! Lets an application act as a Plex Companion player, so that Plex apps can
+//! cast to it and control its playback.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, io,
+    net::{IpAddr, SocketAddr, TcpListener, TcpStream, UdpSocket},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Condvar, Mutex, MutexGuard, PoisonError,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use isahc::{config::Configurable, ReadResponseExt};
+use serde::Serialize;
+
+use crate::{
+    gdm::Responder,
+    http_server::{read_request, wake_listener, write_response, Request},
+    media_container::player::{
+        PlaybackType, Player as PlayerResources, ProtocolCapability, ResourcesMediaContainer,
+        Timeline, TimelineMediaContainer,
+    },
+    NavigationCommand, Result,
+};
+
+/// How long a controller's timeline poll waits for a change.
+const POLL_TIMEOUT: Duration = Duration::from_secs(30);
+/// Controllers have to renew their subscription within this time.
+const SUBSCRIPTION_TIMEOUT: Duration = Duration::from_secs(90);
+const PUSH_TIMEOUT: Duration = Duration::from_secs(5);
+/// Companion commands carry everything in the query, so bodies stay small.
+const MAX_REQUEST_BODY: usize = 16 * 1024;
+/// The most requests handled at once, further connections get a 503.
+const MAX_CONNECTIONS: usize = 32;
+/// The most controllers tracked at once.
+const MAX_CONTROLLERS: usize = 64;
+const OK_RESPONSE: &str = r#"<Response code="200" status="OK"/>"#;
+
+/// What a controller asked to play, and the server to retrieve it from.
+#[derive(Debug, Clone)]
+pub struct PlayMediaRequest {
+    /// The key of the item, e.g. `/library/metadata/55`.
+    pub key: String,
+    /// The position to start at in milliseconds.
+    pub offset: u64,
+    pub playback_type: PlaybackType,
+    pub machine_identifier: Option<String>,
+    pub address: Option<String>,
+    pub port: Option<u16>,
+    pub protocol: Option<String>,
+    /// An access token for the server.
+    pub token: Option<String>,
+    /// The play queue to continue with, e.g. `/playQueues/4211?own=1`.
+    pub container_key: Option<String>,
+}
+
+impl PlayMediaRequest {
+    fn from_request(request: &Request, playback_type: PlaybackType) -> Option<Self> {
+        Some(Self {
+            key: request.param("key")?.to_owned(),
+            offset: request
+                .param("offset")
+                .and_then(|offset| offset.parse().ok())
+                .unwrap_or_default(),
+            playback_type,
+            machine_identifier: request.param("machineIdentifier").map(str::to_owned),
+            address: request.param("address").map(str::to_owned),
+            port: request.param("port").and_then(|port| port.parse().ok()),
+            protocol: request.param("protocol").map(str::to_owned),
+            token: request.param("token").map(str::to_owned),
+            container_key: request.param("containerKey").map(str::to_owned),
+        })
+    }
+
+    /// The address of the server, for use with [`Server::new`](crate::Server::new).
+    pub fn server_url(&self) -> Option<String> {
+        let address = self.address.as_ref()?;
+        Some(format!(
+            "{}://{address}:{}",
+            self.protocol.as_deref().unwrap_or("http"),
+            self.port.unwrap_or(32400)
+        ))
+    }
+}
+
+/// Carries out the commands sent to a [`Receiver`].
+///
+/// Only the basic playback commands have to be implemented. The rest are
+/// ignored unless the matching capability is advertised and the method is
+/// implemented.
+pub trait ReceiverHandler: Send + 'static {
+    fn play_media(&mut self, request: PlayMediaRequest);
+
+    fn play(&mut self, playback_type: PlaybackType);
+
+    fn pause(&mut self, playback_type: PlaybackType);
+
+    fn stop(&mut self, playback_type: PlaybackType);
+
+    /// Jumps to the offset in milliseconds.
+    fn seek_to(&mut self, playback_type: PlaybackType, offset: u64);
+
+    fn skip_next(&mut self, _playback_type: PlaybackType) {}
+
+    fn skip_previous(&mut self, _playback_type: PlaybackType) {}
+
+    fn set_volume(&mut self, _playback_type: PlaybackType, _volume: u8) {}
+
+    fn set_streams(
+        &mut self,
+        _playback_type: PlaybackType,
+        _audio_stream_id: Option<u64>,
+        _subtitle_stream_id: Option<u64>,
+    ) {
+    }
+
+    fn navigate(&mut self, _command: NavigationCommand) {}
+
+    /// Shows the details of the item without playing it.
+    fn mirror(&mut self, _request: PlayMediaRequest) {}
+
+    /// Returns what is currently playing, ideally one timeline for each
+    /// type of media.
+    fn timelines(&self) -> Vec<Timeline>;
+}
+
+#[derive(Debug)]
+struct Controller {
+    last_command_id: u64,
+    /// The timelines version last returned from a poll.
+    seen_version: Option<u64>,
+    subscription: Option<Subscription>,
+    last_seen: Instant,
+}
+
+impl Controller {
+    fn new() -> Self {
+        Self {
+            last_command_id: 0,
+            seen_version: None,
+            subscription: None,
+            last_seen: Instant::now(),
+        }
+    }
+
+    /// Whether the controller is subscribed or sent a request recently.
+    fn is_active(&self) -> bool {
+        self.last_seen.elapsed() <= SUBSCRIPTION_TIMEOUT
+            || self
+                .subscription
+                .as_ref()
+                .is_some_and(|s| s.renewed.elapsed() <= SUBSCRIPTION_TIMEOUT)
+    }
+}
+
+#[derive(Debug)]
+struct Subscription {
+    url: String,
+    renewed: Instant,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Increased whenever the timelines change.
+    version: u64,
+    controllers: HashMap<String, Controller>,
+}
+
+/// The subscribers waiting for the timelines to be pushed to them.
+#[derive(Debug, Default)]
+struct PushQueue {
+    all: bool,
+    controllers: HashSet<String>,
+    closed: bool,
+}
+
+struct Shared {
+    resources: ResourcesMediaContainer,
+    handler: Mutex<Box<dyn ReceiverHandler>>,
+    state: Mutex<State>,
+    changed: Condvar,
+    pushes: Mutex<PushQueue>,
+    push_ready: Condvar,
+    connections: AtomicUsize,
+    http_client: isahc::HttpClient,
+}
+
+/// A poisoned lock only means a handler panicked, the state is still usable.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Advertises the application as a player and hands the commands it receives
+/// to a [`ReceiverHandler`]. Controllers can poll or subscribe to the
+/// player's timelines.
+///
+/// The receiver stops listening when it is dropped.
+pub struct Receiver {
+    shared: Arc<Shared>,
+    local_addr: SocketAddr,
+    closed: Arc<AtomicBool>,
+}
+
+impl Receiver {
+    /// Starts accepting commands on the listener. Only the capabilities
+    /// listed in `resources` are advertised to controllers.
+    #[tracing::instrument(level = "debug", skip(listener, handler))]
+    pub fn start<H: ReceiverHandler>(
+        listener: TcpListener,
+        resources: PlayerResources,
+        handler: H,
+    ) -> Result<Self> {
+        let local_addr = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            resources: ResourcesMediaContainer {
+                player: resources,
+                size: Some(1),
+            },
+            handler: Mutex::new(Box::new(handler)),
+            state: Mutex::new(State::default()),
+            changed: Condvar::new(),
+            pushes: Mutex::new(PushQueue::default()),
+            push_ready: Condvar::new(),
+            connections: AtomicUsize::new(0),
+            http_client: isahc::HttpClient::builder().timeout(PUSH_TIMEOUT).build()?,
+        });
+        let closed = Arc::new(AtomicBool::new(false));
+
+        let listener_shared = shared.clone();
+        let listener_closed = closed.clone();
+        thread::Builder::new()
+            .name("plex-receiver".to_owned())
+            .spawn(move || listen(listener, listener_shared, listener_closed))?;
+
+        let pusher_shared = shared.clone();
+        thread::Builder::new()
+            .name("plex-receiver-push".to_owned())
+            .spawn(move || pusher_shared.push_pending())?;
+
+        Ok(Self {
+            shared,
+            local_addr,
+            closed,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Answers GDM searches on the socket so that controllers on the local
+    /// network can find the receiver, see [`player_socket`](crate::gdm::player_socket).
+    pub fn advertise(&self, socket: UdpSocket) -> Result<Responder> {
+        Responder::start(
+            socket,
+            &self.shared.resources.player,
+            self.local_addr.port(),
+        )
+    }
+
+    /// Lets controllers know that the timelines changed, e.g. because
+    /// playback progressed or was stopped on the device itself.
+    pub fn timeline_changed(&self) {
+        self.shared.timeline_changed();
+    }
+}
+
+impl fmt::Debug for Receiver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("machine_identifier", &self.shared.machine_identifier())
+            .field("local_addr", &self.local_addr)
+            .finish()
+    }
+}
+
+impl Drop for Receiver {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::SeqCst);
+        lock(&self.shared.pushes).closed = true;
+        self.shared.push_ready.notify_all();
+        wake_listener(self.local_addr);
+    }
+}
+
+/// Frees up a connection slot once a request was handled.
+struct ConnectionSlot(Arc<Shared>);
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.connections.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn listen(listener: TcpListener, shared: Arc<Shared>, closed: Arc<AtomicBool>) {
+    for stream in listener.incoming() {
+        if closed.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                tracing::warn!(%error, "Failed to accept a connection");
+                continue;
+            }
+        };
+        if shared.connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            shared.connections.fetch_sub(1, Ordering::SeqCst);
+            tracing::warn!("Too many connections, refusing a request");
+            let _ = shared.respond(&stream, 503, "");
+            continue;
+        }
+        let slot = ConnectionSlot(shared.clone());
+        // Timeline polls can take a while, so every request gets a thread.
+        thread::spawn(move || {
+            if let Err(error) = slot.0.handle(&stream) {
+                tracing::warn!(%error, "Failed to handle a request");
+            }
+        });
+    }
+}
+
+fn to_xml<T: Serialize>(value: &T) -> io::Result<String> {
+    quick_xml::se::to_string_with_root("MediaContainer", value)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+impl Shared {
+    fn machine_identifier(&self) -> &str {
+        &self.resources.player.machine_identifier
+    }
+
+    /// Whether the commands in the section match an advertised capability.
+    fn supports(&self, section: &str) -> bool {
+        let capability = match section {
+            "/player/playback" => ProtocolCapability::Playback,
+            "/player/timeline" => ProtocolCapability::Timeline,
+            "/player/navigation" => ProtocolCapability::Navigation,
+            "/player/mirror" => ProtocolCapability::Mirror,
+            _ => return true,
+        };
+        self.resources
+            .player
+            .protocol_capabilities
+            .contains(&capability)
+    }
+
+    fn respond(&self, stream: &TcpStream, status: u16, body: &str) -> io::Result<()> {
+        write_response(
+            stream,
+            status,
+            &[
+                ("Content-Type", "text/xml"),
+                ("X-Plex-Client-Identifier", self.machine_identifier()),
+                ("Access-Control-Allow-Origin", "*"),
+            ],
+            body,
+        )
+    }
+
+    fn timelines(&self, command_id: u64) -> io::Result<String> {
+        let timelines = lock(&self.handler).timelines();
+        to_xml(&TimelineMediaContainer::new(Some(command_id), timelines))
+    }
+
+    fn handle(&self, stream: &TcpStream) -> io::Result<()> {
+        let request = match read_request(stream, MAX_REQUEST_BODY) {
+            Ok(request) => request,
+            Err(error) => {
+                if let Some(status) = error.status() {
+                    self.respond(stream, status, "")?;
+                }
+                return Err(error.into());
+            }
+        };
+        // Web controllers check whether they're allowed to send commands.
+        if request.method == "OPTIONS" {
+            return self.respond(stream, 200, "");
+        }
+
+        let client_identifier = request
+            .header("X-Plex-Client-Identifier")
+            .unwrap_or_default()
+            .to_owned();
+
+        let command_id = request.param("commandID").and_then(|id| id.parse().ok());
+        let command_id = if client_identifier.is_empty() {
+            command_id.unwrap_or_default()
+        } else {
+            let mut state = lock(&self.state);
+            state
+                .controllers
+                .retain(|_, controller| controller.is_active());
+            if !state.controllers.contains_key(&client_identifier)
+                && state.controllers.len() >= MAX_CONTROLLERS
+            {
+                drop(state);
+                return self.respond(stream, 503, "");
+            }
+            let controller = state
+                .controllers
+                .entry(client_identifier.clone())
+                .or_insert_with(Controller::new);
+            controller.last_seen = Instant::now();
+            if let Some(command_id) = command_id {
+                controller.last_command_id = command_id;
+            }
+            controller.last_command_id
+        };
+
+        let playback_type = request
+            .param("type")
+            .and_then(|t| t.parse().ok())
+            .unwrap_or(PlaybackType::Video);
+
+        let path = request.path.trim_end_matches('/');
+        let (section, command) = path.rsplit_once('/').unwrap_or_default();
+        if !self.supports(section) {
+            return self.respond(stream, 404, "");
+        }
+
+        match (section, command) {
+            ("", "resources") => self.respond(stream, 200, &to_xml(&self.resources)?),
+            ("/player/timeline", "poll") => {
+                let wait = request.param("wait") == Some("1");
+                self.wait_for_change(&client_identifier, wait);
+                self.respond(stream, 200, &self.timelines(command_id)?)
+            }
+            ("/player/timeline", "subscribe") => {
+                let port = request.param("port").and_then(|port| port.parse().ok());
+                let protocol = request.param("protocol").unwrap_or("http");
+                let (Some(port), "http" | "https") = (port, protocol) else {
+                    return self.respond(stream, 400, "");
+                };
+                if client_identifier.is_empty() {
+                    return self.respond(stream, 400, "");
+                }
+                let ip = stream.peer_addr()?.ip();
+                self.subscribe(&client_identifier, protocol, ip, port);
+                self.respond(stream, 200, OK_RESPONSE)?;
+                self.queue_push(Some(client_identifier));
+                Ok(())
+            }
+            ("/player/timeline", "unsubscribe") => {
+                if let Some(controller) = lock(&self.state).controllers.get_mut(&client_identifier)
+                {
+                    controller.subscription = None;
+                }
+                self.respond(stream, 200, OK_RESPONSE)
+            }
+            _ => {
+                if !self.dispatch(section, command, &request, playback_type) {
+                    return self.respond(stream, 404, "");
+                }
+                self.respond(stream, 200, OK_RESPONSE)?;
+                self.timeline_changed();
+                Ok(())
+            }
+        }
+    }
+
+    /// Hands a command to the handler, returns whether it was recognized.
+    fn dispatch(
+        &self,
+        section: &str,
+        command: &str,
+        request: &Request,
+        playback_type: PlaybackType,
+    ) -> bool {
+        let mut handler = lock(&self.handler);
+        let param = |name| request.param(name).and_then(|value| value.parse().ok());
+
+        match (section, command) {
+            ("/player/playback", "playMedia") => {
+                match PlayMediaRequest::from_request(request, playback_type) {
+                    Some(play_media) => handler.play_media(play_media),
+                    None => return false,
+                }
+            }
+            ("/player/playback", "play") => handler.play(playback_type),
+            ("/player/playback", "pause") => handler.pause(playback_type),
+            ("/player/playback", "stop") => handler.stop(playback_type),
+            ("/player/playback", "skipNext") => handler.skip_next(playback_type),
+            ("/player/playback", "skipPrevious") => handler.skip_previous(playback_type),
+            ("/player/playback", "seekTo") => {
+                handler.seek_to(playback_type, param("offset").unwrap_or_default())
+            }
+            ("/player/playback", "setParameters") => {
+                if let Some(volume) = request.param("volume").and_then(|v| v.parse().ok()) {
+                    handler.set_volume(playback_type, volume);
+                }
+            }
+            ("/player/playback", "setStreams") => handler.set_streams(
+                playback_type,
+                param("audioStreamID"),
+                param("subtitleStreamID"),
+            ),
+            ("/player/navigation", command) => match command.parse() {
+                Ok(command) => handler.navigate(command),
+                Err(_) => return false,
+            },
+            ("/player/mirror", "details") => {
+                match PlayMediaRequest::from_request(request, playback_type) {
+                    Some(mirror) => handler.mirror(mirror),
+                    None => return false,
+                }
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Blocks until the timelines changed since the controller last polled
+    /// them, or returns straight away if it never polled before.
+    fn wait_for_change(&self, client_identifier: &str, wait: bool) {
+        let mut state = lock(&self.state);
+        let deadline = Instant::now() + POLL_TIMEOUT;
+
+        loop {
+            let version = state.version;
+            let seen = state
+                .controllers
+                .get(client_identifier)
+                .and_then(|c| c.seen_version);
+            let now = Instant::now();
+            if !wait || seen != Some(version) || now >= deadline {
+                break;
+            }
+            state = self
+                .changed
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+
+        let version = state.version;
+        if let Some(controller) = state.controllers.get_mut(client_identifier) {
+            controller.seen_version = Some(version);
+        }
+    }
+
+    fn subscribe(&self, client_identifier: &str, protocol: &str, ip: IpAddr, port: u16) {
+        let url = format!("{protocol}://{}/:/timeline", SocketAddr::new(ip, port));
+        lock(&self.state)
+            .controllers
+            .entry(client_identifier.to_owned())
+            .or_insert_with(Controller::new)
+            .subscription = Some(Subscription {
+            url,
+            renewed: Instant::now(),
+        });
+    }
+
+    fn timeline_changed(&self) {
+        lock(&self.state).version += 1;
+        self.changed.notify_all();
+        self.queue_push(None);
+    }
+
+    /// Asks the push thread to send the timelines to all subscribers, or
+    /// just the given one.
+    fn queue_push(&self, client_identifier: Option<String>) {
+        let mut pushes = lock(&self.pushes);
+        match client_identifier {
+            Some(client_identifier) => {
+                pushes.controllers.insert(client_identifier);
+            }
+            None => pushes.all = true,
+        }
+        self.push_ready.notify_one();
+    }
+
+    /// Runs on the push thread until the receiver is dropped. Changes that
+    /// pile up while a push is in progress are sent together afterwards.
+    fn push_pending(&self) {
+        loop {
+            let (all, controllers) = {
+                let mut pushes = lock(&self.pushes);
+                while !pushes.closed && !pushes.all && pushes.controllers.is_empty() {
+                    pushes = self
+                        .push_ready
+                        .wait(pushes)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+                if pushes.closed {
+                    return;
+                }
+                (
+                    std::mem::take(&mut pushes.all),
+                    std::mem::take(&mut pushes.controllers),
+                )
+            };
+            self.push_timelines((!all).then_some(&controllers));
+        }
+    }
+
+    /// Sends the timelines to all subscribers, or just the given ones.
+    fn push_timelines(&self, only: Option<&HashSet<String>>) {
+        let targets = {
+            let mut state = lock(&self.state);
+            let mut targets = Vec::new();
+            for (client_identifier, controller) in state.controllers.iter_mut() {
+                if only.is_some_and(|only| !only.contains(client_identifier)) {
+                    continue;
+                }
+                if controller
+                    .subscription
+                    .as_ref()
+                    .is_some_and(|s| s.renewed.elapsed() > SUBSCRIPTION_TIMEOUT)
+                {
+                    controller.subscription = None;
+                }
+                if let Some(ref subscription) = controller.subscription {
+                    targets.push((subscription.url.clone(), controller.last_command_id));
+                }
+            }
+            targets
+        };
+
+        for (url, command_id) in targets {
+            if let Err(error) = self.push_timeline(&url, command_id) {
+                tracing::warn!(%error, url, "Failed to push the timelines");
+            }
+        }
+    }
+
+    fn push_timeline(&self, url: &str, command_id: u64) -> Result {
+        let request = isahc::Request::post(url)
+            .header("Content-Type", "text/xml")
+            .header("X-Plex-Client-Identifier", self.machine_identifier())
+            .body(self.timelines(command_id)?)?;
+        self.http_client.send(request)?.consume()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+use std::{collections::HashSet, time::Duration};
+
+use futures_timer::Delay;
+use http::{uri::PathAndQuery, StatusCode};
+use isahc::AsyncReadResponseExt;
+
+use crate::{
+    http_client::RequestBuilder,
+    media_container::{
+        server::activity::{ActivitiesMediaContainer, Activity},
+        MediaContainerWrapper,
+    },
+    url::SERVER_ACTIVITIES,
+    Error, HttpClient, Result,
+};
+
+/// The number of polls without seeing the activity after which it's assumed
+/// to have finished before it could be observed.
+const ACTIVITY_GRACE_POLLS: u32 = 3;
+
+/// Retrieves the activities currently running on the server.
+#[tracing::instrument(level = "trace", skip(client))]
+pub(crate) async fn activities(client: &HttpClient) -> Result<Vec<Activity>> {
+    let wrapper: MediaContainerWrapper<ActivitiesMediaContainer> =
+        client.get(SERVER_ACTIVITIES).json().await?;
+    Ok(wrapper.media_container.activities)
+}
+
+/// Cancels the activity with the passed uuid.
+#[tracing::instrument(level = "trace", skip(client))]
+pub(crate) async fn cancel_activity(client: &HttpClient, uuid: &str) -> Result {
+    client
+        .delete(format!("{SERVER_ACTIVITIES}/{uuid}"))
+        .consume()
+        .await
+}
+
+/// Sends the request starting a server activity and returns a handle
+/// tracking it. For operations on a library section the activity is the one
+/// started for that section.
+pub(crate) async fn start_activity<P>(
+    client: &HttpClient,
+    request: RequestBuilder<'_, P>,
+    library_section_id: Option<&str>,
+) -> Result<ActivityHandle>
+where
+    PathAndQuery: TryFrom<P>,
+    <PathAndQuery as TryFrom<P>>::Error: Into<http::Error>,
+{
+    // Not every endpoint reports the activity it starts, so the activities
+    // that already exist are needed to recognize the new one.
+    let known = activities(client)
+        .await?
+        .into_iter()
+        .map(|activity| activity.uuid)
+        .collect();
+
+    let mut response = request.send().await?;
+    if response.status() != StatusCode::OK {
+        return Err(Error::from_response(response).await);
+    }
+
+    let uuid = response
+        .headers()
+        .get("X-Plex-Activity")
+        .and_then(|v| v.to_str().ok())
+        .map(ToOwned::to_owned);
+    response.consume().await?;
+
+    Ok(ActivityHandle {
+        client: client.clone(),
+        known,
+        uuid,
+        library_section_id: library_section_id.map(ToOwned::to_owned),
+    })
+}
+
+/// A handle to the server activity started by a maintenance operation, e.g.
+/// a library scan.
+///
+/// The server doesn't always report which activity was started, in that case
+/// the first new activity (for the library section, if any) is tracked.
+#[derive(Debug, Clone)]
+pub struct ActivityHandle {
+    client: HttpClient,
+    known: HashSet<String>,
+    uuid: Option<String>,
+    library_section_id: Option<String>,
+}
+
+impl ActivityHandle {
+    fn matches(&self, activity: &Activity) -> bool {
+        if let Some(ref uuid) = self.uuid {
+            return &activity.uuid == uuid;
+        }
+
+        if self.known.contains(&activity.uuid) {
+            return false;
+        }
+
+        match self.library_section_id {
+            Some(ref id) => {
+                activity
+                    .context
+                    .as_ref()
+                    .and_then(|c| c.library_section_id.as_ref())
+                    == Some(id)
+            }
+            None => true,
+        }
+    }
+
+    /// Retrieves the current state of the activity, or `None` if it isn't
+    /// running.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn activity(&self) -> Result<Option<Activity>> {
+        let activity = activities(&self.client)
+            .await?
+            .into_iter()
+            .find(|activity| self.matches(activity));
+
+        Ok(activity)
+    }
+
+    /// Cancels the activity if it's still running. Not every activity can be
+    /// cancelled, see [`Activity::cancellable`].
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn cancel(&self) -> Result {
+        match self.activity().await? {
+            Some(activity) => cancel_activity(&self.client, &activity.uuid).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Waits for the activity to finish, checking on it every `poll_interval`.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn wait(&mut self, poll_interval: Duration) -> Result<()> {
+        let mut seen = false;
+        let mut misses = 0;
+
+        loop {
+            match self.activity().await? {
+                Some(activity) => {
+                    // Stick to the activity once it was found, so that
+                    // another one starting later isn't mistaken for it.
+                    self.uuid = Some(activity.uuid);
+                    seen = true;
+                }
+                None if seen || misses >= ACTIVITY_GRACE_POLLS => {
+                    return Ok(());
+                }
+                None => misses += 1,
+            }
+
+            Delay::new(poll_interval).await;
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use time::OffsetDateTime;
+
+use crate::{
+    media_container::server::library::{Guid, Metadata},
+    url::SERVER_HISTORY,
+    HttpClient, Result,
+};
+
+use super::library::{
+    item_by_id, metadata_items_stream, FromMetadata, Item, ItemStream, MetadataItem, PagingOptions,
+};
+
+/// Restricts the entries returned from the watch history. Entries are always
+/// returned most recent first.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryOptions {
+    /// Only include items watched by the account.
+    pub account_id: Option<u64>,
+    /// Only include items from the library.
+    pub library_section_id: Option<String>,
+    /// Only include items watched after this time.
+    pub viewed_after: Option<OffsetDateTime>,
+    /// Only include items watched before this time.
+    pub viewed_before: Option<OffsetDateTime>,
+    /// Only include the item with this rating key.
+    pub rating_key: Option<String>,
+}
+
+impl HistoryOptions {
+    fn query(&self) -> Result<String> {
+        let mut params = vec![("sort", "viewedAt:desc".to_owned())];
+
+        if let Some(account_id) = self.account_id {
+            params.push(("accountID", account_id.to_string()));
+        }
+        if let Some(ref library_section_id) = self.library_section_id {
+            params.push(("librarySectionID", library_section_id.clone()));
+        }
+        if let Some(viewed_after) = self.viewed_after {
+            params.push(("viewedAt>", viewed_after.unix_timestamp().to_string()));
+        }
+        if let Some(viewed_before) = self.viewed_before {
+            params.push(("viewedAt<", viewed_before.unix_timestamp().to_string()));
+        }
+        if let Some(ref rating_key) = self.rating_key {
+            params.push(("metadataItemID", rating_key.clone()));
+        }
+
+        Ok(serde_urlencoded::to_string(params)?)
+    }
+}
+
+/// A single view of an item.
+///
+/// The history only includes basic information about the item, use
+/// [`HistoryEntry::item`] to retrieve the full item.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    client: HttpClient,
+    metadata: Metadata,
+}
+
+impl FromMetadata for HistoryEntry {
+    fn from_metadata(client: HttpClient, metadata: Metadata) -> Self {
+        Self { client, metadata }
+    }
+}
+
+impl MetadataItem for HistoryEntry {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn client(&self) -> &HttpClient {
+        &self.client
+    }
+}
+
+impl HistoryEntry {
+    /// Returns the time the item was watched.
+    pub fn viewed_at(&self) -> Option<OffsetDateTime> {
+        self.metadata.viewed_at
+    }
+
+    /// Returns the id of the account that watched the item.
+    pub fn account_id(&self) -> Option<u64> {
+        self.metadata.account_id
+    }
+
+    /// Returns the id of the device the item was watched on.
+    pub fn device_id(&self) -> Option<u64> {
+        self.metadata.device_id
+    }
+
+    /// Returns the guid of the item, which can be used to match it with
+    /// external databases.
+    pub fn guid(&self) -> Option<&Guid> {
+        self.metadata.guid.as_ref()
+    }
+
+    /// Retrieves the watched item from the server. Fails with
+    /// [`crate::Error::ItemNotFound`] if the item was since removed.
+    #[tracing::instrument(level = "debug", skip(self), fields(item.rating_key = self.metadata.rating_key))]
+    pub async fn item(&self) -> Result<Item> {
+        item_by_id(&self.client, &self.metadata.rating_key).await
+    }
+}
+
+/// Streams the entries in the watch history.
+#[tracing::instrument(level = "trace", skip(client))]
+pub(crate) async fn history(
+    client: &HttpClient,
+    options: &HistoryOptions,
+    paging: PagingOptions,
+) -> Result<ItemStream<HistoryEntry>> {
+    let path = format!("{SERVER_HISTORY}?{}", options.query()?);
+    metadata_items_stream(client, &path, paging).await
+}
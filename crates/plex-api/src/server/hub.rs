@@ -0,0 +1,84 @@
+use crate::{
+    media_container::{
+        server::hub::{Hub as HubMetadata, HubsMediaContainer},
+        MediaContainerWrapper,
+    },
+    HttpClient, Result,
+};
+
+use super::library::{metadata_items, FromMetadata, Item};
+
+/// Retrieves the hubs given the lookup key.
+#[tracing::instrument(level = "trace", skip(client))]
+pub(crate) async fn hubs(client: &HttpClient, path: &str) -> Result<Vec<Hub>> {
+    let wrapper: MediaContainerWrapper<HubsMediaContainer> = client.get(path).json().await?;
+
+    Ok(wrapper
+        .media_container
+        .hubs
+        .into_iter()
+        .map(|hub| Hub {
+            client: client.clone(),
+            hub,
+        })
+        .collect())
+}
+
+/// A list of items grouped by the server, as shown on the home screen or
+/// returned by a search.
+#[derive(Debug, Clone)]
+pub struct Hub {
+    client: HttpClient,
+    hub: HubMetadata,
+}
+
+impl Hub {
+    /// Returns the title of this hub.
+    pub fn title(&self) -> &str {
+        &self.hub.title
+    }
+
+    /// Returns the identifier of this hub, e.g. `home.ondeck` or
+    /// `movie.recentlyadded.1`.
+    pub fn identifier(&self) -> &str {
+        &self.hub.hub_identifier
+    }
+
+    /// Returns the context of this hub, e.g. `hub.home.ondeck`.
+    pub fn context(&self) -> &str {
+        &self.hub.context
+    }
+
+    /// Returns the type of the items in this hub, e.g. `movie` or `mixed`.
+    pub fn hub_type(&self) -> &str {
+        &self.hub.hub_type
+    }
+
+    /// Whether the server has more items for this hub than were included.
+    pub fn has_more(&self) -> bool {
+        self.hub.more.unwrap_or_default()
+    }
+
+    pub fn metadata(&self) -> &HubMetadata {
+        &self.hub
+    }
+
+    /// Returns the items included with this hub. Hubs usually only include
+    /// the first few items, use [`Hub::all_items`] to retrieve the rest.
+    pub fn items(&self) -> Vec<Item> {
+        self.hub
+            .metadata
+            .iter()
+            .map(|metadata| Item::from_metadata(self.client.clone(), metadata.clone()))
+            .collect()
+    }
+
+    /// Retrieves all of the items in this hub.
+    #[tracing::instrument(level = "debug", skip(self), fields(hub.identifier = self.hub.hub_identifier))]
+    pub async fn all_items(&self) -> Result<Vec<Item>> {
+        match self.hub.key {
+            Some(ref key) if self.has_more() => metadata_items(&self.client, key).await,
+            _ => Ok(self.items()),
+        }
+    }
+}
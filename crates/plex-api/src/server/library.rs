@@ -1,24 +1,51 @@
 use std::{future::Future, marker::PhantomData, ops::RangeBounds};
 
 use enum_dispatch::enum_dispatch;
-use futures::AsyncWrite;
+use futures::{AsyncRead, AsyncWrite};
 use http::StatusCode;
 use isahc::AsyncReadResponseExt;
 
 use crate::{
     media_container::{
         server::library::{
-            CollectionMetadataSubtype, LibraryType, Media as MediaMetadata, Metadata,
-            MetadataMediaContainer, MetadataType, Part as PartMetadata, PlaylistMetadataType,
-            Protocol, SearchType, ServerLibrary,
+            Artwork, AudioStream, CollectionMetadataSubtype, LibraryType, Location,
+            Media as MediaMetadata, Metadata, MetadataMediaContainer, MetadataType,
+            Part as PartMetadata, PlaylistMetadataType, Protocol, SearchType, ServerLibrary,
+            Stream, SubtitleStream,
         },
         MediaContainerWrapper,
     },
+    myplex::account::Profile,
     transcode::{MusicTranscodeOptions, TranscodeSession, VideoTranscodeOptions},
+    url::{SERVER_COLLECTIONS, SERVER_HUBS_SEARCH, SERVER_PLAYLISTS},
     Error, HttpClient, Result,
 };
 
-use super::transcode::{create_transcode_session, Context, TranscodeOptions};
+use super::{
+    activity::{start_activity, ActivityHandle},
+    hub::{hubs, Hub},
+    prefs::Preferences,
+    transcode::{create_transcode_session, Context, TranscodeOptions},
+    Query,
+};
+
+mod artwork;
+mod edit;
+mod matching;
+mod paging;
+mod search;
+mod section;
+mod streams;
+
+pub use self::artwork::ArtworkKind;
+pub use self::edit::{MetadataEdit, TagField};
+pub use self::matching::{MatchCandidate, MatchFeature};
+pub(crate) use self::paging::metadata_items_stream;
+pub use self::paging::{ItemStream, PagingOptions};
+pub use self::search::{LibrarySearch, Operator, Resolution};
+pub(crate) use self::section::library_sections;
+pub use self::section::NewLibrary;
+pub use self::streams::{StreamChoice, StreamSelectionScope};
 
 pub trait FromMetadata {
     /// Creates an item given the http configuration and item metadata. No
@@ -58,8 +85,101 @@ pub trait MetadataItem {
     fn title(&self) -> &str {
         &self.metadata().title
     }
+
+    /// Whether the field is protected from changes by the agents when the
+    /// metadata is refreshed.
+    fn is_locked(&self, field: &str) -> bool {
+        self.metadata()
+            .fields
+            .iter()
+            .any(|f| f.locked && f.name == field)
+    }
+
+    /// Starts editing the metadata of this item.
+    fn edit(&self) -> MetadataEdit<'_> {
+        MetadataEdit::new(self.client(), self.metadata())
+    }
+}
+
+/// Fixing the match of an item with the agents, available for every
+/// [`MetadataItem`]. The match actions need the server's [`MatchFeature`].
+pub trait MetadataItemWithMatching: MetadataItem {
+    /// Searches the agent for what this item could be, as the Fix Match
+    /// dialog in Plex Web does. Parameters that aren't given are left out,
+    /// the server then uses the item's title and year and its library's
+    /// agent.
+    fn search_matches(
+        &self,
+        feature: &MatchFeature,
+        title: Option<&str>,
+        year: Option<u32>,
+        agent: Option<&str>,
+    ) -> impl Future<Output = Result<Vec<MatchCandidate>>> + Send {
+        matching::search_matches(self.client(), feature, self.metadata(), title, year, agent)
+    }
+
+    /// Matches this item to the candidate, replacing its metadata with the
+    /// candidate's.
+    fn apply_match(
+        &self,
+        feature: &MatchFeature,
+        candidate: &MatchCandidate,
+    ) -> impl Future<Output = Result> + Send {
+        matching::apply_match(self.client(), feature, self.metadata(), candidate)
+    }
+
+    /// Removes the match of this item, leaving it with the metadata that can
+    /// be gathered from its files only.
+    fn unmatch(&self, feature: &MatchFeature) -> impl Future<Output = Result> + Send {
+        matching::unmatch(self.client(), feature, self.metadata())
+    }
+
+    /// Retrieves the metadata of this item from the agents again.
+    fn refresh_metadata(&self) -> impl Future<Output = Result> + Send {
+        matching::refresh_metadata(self.client(), self.metadata())
+    }
 }
 
+impl<T: MetadataItem> MetadataItemWithMatching for T {}
+
+/// Choosing the artwork of an item, available for every [`MetadataItem`].
+pub trait MetadataItemWithArtwork: MetadataItem {
+    /// Lists the artwork of the kind that can be selected for this item,
+    /// both the ones provided by the agents and the uploaded ones.
+    fn artwork(&self, kind: ArtworkKind) -> impl Future<Output = Result<Vec<Artwork>>> + Send {
+        artwork::artwork(self.client(), self.metadata(), kind)
+    }
+
+    /// Selects one of the artwork returned by
+    /// [`MetadataItemWithArtwork::artwork`].
+    fn select_artwork(
+        &self,
+        kind: ArtworkKind,
+        artwork: &Artwork,
+    ) -> impl Future<Output = Result> + Send {
+        artwork::select_artwork(self.client(), self.metadata(), kind, artwork)
+    }
+
+    /// Makes the server download the artwork at the URL and selects it.
+    fn upload_artwork_url(
+        &self,
+        kind: ArtworkKind,
+        url: &str,
+    ) -> impl Future<Output = Result> + Send {
+        artwork::upload_artwork_url(self.client(), self.metadata(), kind, url)
+    }
+
+    /// Uploads the artwork read from `data` and selects it.
+    fn upload_artwork<R>(&self, kind: ArtworkKind, data: R) -> impl Future<Output = Result> + Send
+    where
+        R: AsyncRead + Send + Sync + 'static,
+    {
+        artwork::upload_artwork(self.client(), self.metadata(), kind, data)
+    }
+}
+
+impl<T: MetadataItem> MetadataItemWithArtwork for T {}
+
 /// Implements MetadataItem for the given struct which must contain `client`
 /// and `metadata` fields.
 macro_rules! derive_metadata_item {
@@ -87,16 +207,13 @@ macro_rules! derive_metadata_item {
     };
 }
 
-/// Retrieves a list of metadata items given the lookup key.
-#[tracing::instrument(level = "trace", skip(client))]
-pub(crate) async fn metadata_items<T>(client: &HttpClient, path: &str) -> Result<Vec<T>>
+/// Converts the metadata in a container to items, filling in the library
+/// details that the server only includes on the container itself.
+fn container_items<T>(client: &HttpClient, container: MetadataMediaContainer) -> Vec<T>
 where
     T: FromMetadata,
 {
-    let wrapper: MediaContainerWrapper<MetadataMediaContainer> = client.get(path).json().await?;
-
-    let media = wrapper
-        .media_container
+    container
         .metadata
         .into_iter()
         .map(|metadata| {
@@ -105,16 +222,72 @@ where
                 Metadata {
                     library_section_id: metadata
                         .library_section_id
-                        .or(wrapper.media_container.library_section_id),
+                        .or(container.library_section_id),
                     library_section_title: metadata
                         .library_section_title
-                        .or(wrapper.media_container.library_section_title.clone()),
+                        .or(container.library_section_title.clone()),
                     ..metadata
                 },
             )
         })
-        .collect();
-    Ok(media)
+        .collect()
+}
+
+/// Retrieves a list of metadata items given the lookup key.
+#[tracing::instrument(level = "trace", skip(client))]
+pub(crate) async fn metadata_items<T>(client: &HttpClient, path: &str) -> Result<Vec<T>>
+where
+    T: FromMetadata,
+{
+    let wrapper: MediaContainerWrapper<MetadataMediaContainer> = client.get(path).json().await?;
+    Ok(container_items(client, wrapper.media_container))
+}
+
+/// Retrieves a single page of metadata items given the lookup key. Returns
+/// the items along with the total number of items if the server reported it.
+#[tracing::instrument(level = "trace", skip(client))]
+pub(crate) async fn metadata_page<T>(
+    client: &HttpClient,
+    path: &str,
+    start: u32,
+    size: u32,
+) -> Result<(Vec<T>, Option<u32>)>
+where
+    T: FromMetadata,
+{
+    let wrapper: MediaContainerWrapper<MetadataMediaContainer> = client
+        .get(path)
+        .header("X-Plex-Container-Start", start.to_string())
+        .header("X-Plex-Container-Size", size.to_string())
+        .json()
+        .await?;
+    let total_size = wrapper.media_container.media_container.total_size;
+    Ok((container_items(client, wrapper.media_container), total_size))
+}
+
+/// Retrieves an item given its rating key.
+#[tracing::instrument(level = "trace", skip(client))]
+pub(crate) async fn item_by_id(client: &HttpClient, rating_key: &str) -> Result<Item> {
+    let path = format!("/library/metadata/{rating_key}?includeConcerts=1&includeExtras=1&includePopularLeaves=1&includePreferences=1&includeReviews=1&includeOnDeck=1&includeChapters=1&includeStations=1&includeExternalMedia=1&asyncAugmentMetadata=1&asyncCheckFiles=1&asyncRefreshAnalysis=1&asyncRefreshLocalMediaAgent=1&includeMarkers=1");
+
+    match metadata_items(client, &path).await {
+        Ok(items) => items.into_iter().next().ok_or(Error::ItemNotFound),
+        Err(Error::UnexpectedApiResponse {
+            status_code,
+            content,
+        }) => {
+            // A 404 error indicates the item does not exist.
+            if status_code == 404 {
+                Err(Error::ItemNotFound)
+            } else {
+                Err(Error::UnexpectedApiResponse {
+                    status_code,
+                    content,
+                })
+            }
+        }
+        Err(err) => Err(err),
+    }
 }
 
 /// Attempts to retrieve the parent of this item.
@@ -148,6 +321,24 @@ where
     }
 }
 
+/// Streams the metadata items from a pivot from a library.
+#[tracing::instrument(level = "trace", skip(client, directory), fields(directory.key = directory.key))]
+async fn pivot_items_stream<M>(
+    client: &HttpClient,
+    directory: &ServerLibrary,
+    context: &str,
+    options: PagingOptions,
+) -> Result<ItemStream<M>>
+where
+    M: FromMetadata + Send + 'static,
+{
+    if let Some(pivot) = directory.pivots.iter().find(|p| p.context == context) {
+        metadata_items_stream(client, &pivot.key, options).await
+    } else {
+        Ok(ItemStream::empty())
+    }
+}
+
 /// A single media format for a `MediaItem`.
 #[derive(Debug, Clone)]
 pub struct Media<'a, M: MediaItem> {
@@ -181,6 +372,16 @@ impl<'a, M: MediaItem> Media<'a, M> {
         self.media.duration
     }
 
+    /// The streams the official clients would play for this media given the
+    /// account's language profile. Parts of the same media share a stream
+    /// layout so this is resolved against the first part.
+    pub fn stream_choice(&self, profile: &Profile) -> Option<StreamChoice<'a>> {
+        self.media
+            .parts
+            .first()
+            .map(|part| StreamChoice::resolve(part, profile))
+    }
+
     /// The internal metadata for the media.
     pub fn metadata(&self) -> &MediaMetadata {
         self.media
@@ -287,6 +488,72 @@ impl<'a, M: MediaItem> Part<'a, M> {
         }
     }
 
+    /// The audio streams available in this part.
+    pub fn audio_streams(&self) -> Vec<&'a AudioStream> {
+        self.streams()
+            .filter_map(|stream| match stream {
+                Stream::Audio(stream) => Some(stream),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The subtitle streams available in this part.
+    pub fn subtitle_streams(&self) -> Vec<&'a SubtitleStream> {
+        self.streams()
+            .filter_map(|stream| match stream {
+                Stream::Subtitle(stream) => Some(stream),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn streams(&self) -> impl Iterator<Item = &'a Stream> {
+        self.part.streams.iter().flatten()
+    }
+
+    /// Makes the given stream the default audio for this part. The change
+    /// applies to all parts of the item.
+    ///
+    /// The part's metadata isn't updated, refetch the item to see the change.
+    pub async fn select_audio_stream(
+        &self,
+        stream: &AudioStream,
+        scope: StreamSelectionScope,
+    ) -> Result {
+        streams::select_audio_stream(self.client, self.part, stream, scope).await
+    }
+
+    /// Makes the given stream the default subtitles for this part, `None`
+    /// turning subtitles off. The change applies to all parts of the item.
+    ///
+    /// The part's metadata isn't updated, refetch the item to see the change.
+    pub async fn select_subtitle_stream(
+        &self,
+        stream: Option<&SubtitleStream>,
+        scope: StreamSelectionScope,
+    ) -> Result {
+        streams::select_subtitle_stream(self.client, self.part, &stream.into(), scope).await
+    }
+
+    /// The streams the official clients would play for this part given the
+    /// account's language profile.
+    pub fn stream_choice(&self, profile: &Profile) -> StreamChoice<'a> {
+        StreamChoice::resolve(self.part, profile)
+    }
+
+    /// Makes the chosen streams the default for this part.
+    pub async fn apply_stream_choice(
+        &self,
+        choice: &StreamChoice<'_>,
+        scope: StreamSelectionScope,
+    ) -> Result {
+        if let Some(audio) = choice.audio {
+            self.select_audio_stream(audio, scope).await?;
+        }
+        self.select_subtitle_stream(choice.subtitle, scope).await
+    }
+
     /// The internal metadata for the media.
     pub fn metadata(&self) -> &PartMetadata {
         self.part
@@ -463,6 +730,130 @@ where
     }
 }
 
+impl<M> Playlist<M>
+where
+    M: FromMetadata + Send + 'static,
+{
+    /// Streams the items in this playlist, retrieving them from the server
+    /// a page at a time.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn children_stream(&self, options: PagingOptions) -> Result<ItemStream<M>> {
+        metadata_items_stream(&self.client, &self.metadata.key, options).await
+    }
+}
+
+impl<M> Playlist<M> {
+    /// Whether the items of this playlist are selected by a library filter.
+    pub fn is_smart(&self) -> bool {
+        self.metadata.smart.unwrap_or_default()
+    }
+
+    fn path(&self) -> String {
+        format!("{SERVER_PLAYLISTS}/{}", self.metadata.rating_key)
+    }
+
+    async fn set_field(&self, field: &str, value: &str) -> Result {
+        self.client
+            .put(format!(
+                "{}?{}",
+                self.path(),
+                serde_urlencoded::to_string([(field, value)])?
+            ))
+            .consume()
+            .await
+    }
+
+    /// Changes the title of this playlist.
+    #[tracing::instrument(level = "debug", skip(self), fields(playlist.id = self.rating_key()))]
+    pub async fn rename(&self, title: &str) -> Result {
+        self.set_field("title", title).await
+    }
+
+    /// Changes the summary of this playlist.
+    #[tracing::instrument(level = "debug", skip(self), fields(playlist.id = self.rating_key()))]
+    pub async fn set_summary(&self, summary: &str) -> Result {
+        self.set_field("summary", summary).await
+    }
+
+    /// Sets the poster of this playlist to the image at the URL.
+    #[tracing::instrument(level = "debug", skip(self), fields(playlist.id = self.rating_key()))]
+    pub async fn set_poster(&self, url: &str) -> Result {
+        artwork::upload_artwork_url(&self.client, &self.metadata, ArtworkKind::Poster, url).await
+    }
+
+    /// Deletes this playlist. The items in it are kept.
+    #[tracing::instrument(level = "debug", skip_all, fields(playlist.id = self.rating_key()))]
+    pub async fn delete(self) -> Result {
+        self.client.delete(self.path()).consume().await
+    }
+}
+
+pub(crate) fn playlist_item_id<M: MetadataItem>(item: &M) -> Result<u32> {
+    item.metadata()
+        .playlist_item_id
+        .ok_or(Error::PlaylistItemIdNotAvailable)
+}
+
+/// The kinds of items a playlist can be made of.
+pub trait PlaylistContent: FromMetadata {
+    /// The playlist type used by the server.
+    const PLAYLIST_TYPE: &'static str;
+}
+
+impl PlaylistContent for Video {
+    const PLAYLIST_TYPE: &'static str = "video";
+}
+
+impl PlaylistContent for Track {
+    const PLAYLIST_TYPE: &'static str = "audio";
+}
+
+impl PlaylistContent for Photo {
+    const PLAYLIST_TYPE: &'static str = "photo";
+}
+
+/// Items that can be added to a playlist of `M` items, e.g. a [`Movie`] can
+/// be added to a `Playlist<Video>` but a [`Track`] can't.
+pub trait PlaylistItem<M: PlaylistContent>: MetadataItem {}
+
+impl PlaylistItem<Video> for Video {}
+impl PlaylistItem<Video> for Movie {}
+impl PlaylistItem<Video> for Episode {}
+impl PlaylistItem<Track> for Track {}
+impl PlaylistItem<Photo> for Photo {}
+
+/// Creates a playlist of the items identified by the uri.
+#[tracing::instrument(level = "trace", skip(client))]
+pub(crate) async fn create_playlist<M>(
+    client: &HttpClient,
+    title: &str,
+    smart: bool,
+    uri: &str,
+) -> Result<Playlist<M>>
+where
+    M: PlaylistContent,
+{
+    let query = serde_urlencoded::to_string([
+        ("type", M::PLAYLIST_TYPE),
+        ("title", title),
+        ("smart", if smart { "1" } else { "0" }),
+        ("uri", uri),
+    ])?;
+
+    let wrapper: MediaContainerWrapper<MetadataMediaContainer> = client
+        .post(format!("{SERVER_PLAYLISTS}?{query}"))
+        .json()
+        .await?;
+
+    wrapper
+        .media_container
+        .metadata
+        .into_iter()
+        .next()
+        .map(|metadata| Playlist::from_metadata(client.clone(), metadata))
+        .ok_or(Error::ItemNotFound)
+}
+
 #[derive(Debug, Clone)]
 pub struct Collection<M> {
     _items: PhantomData<M>,
@@ -492,6 +883,167 @@ where
     }
 }
 
+impl<M> Collection<M>
+where
+    M: FromMetadata + Send + 'static,
+{
+    /// Streams the items in this collection, retrieving them from the server
+    /// a page at a time.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn children_stream(&self, options: PagingOptions) -> Result<ItemStream<M>> {
+        metadata_items_stream(&self.client, &self.metadata.key, options).await
+    }
+}
+
+/// Controls how a collection is shown in its library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionMode {
+    /// Use the library's setting.
+    Default = -1,
+    /// Hide the collection, only show its items.
+    Hide = 0,
+    /// Show the collection instead of its items.
+    HideItems = 1,
+    /// Show both the collection and its items.
+    ShowItems = 2,
+}
+
+/// The order of the items in a collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionSort {
+    ReleaseDate = 0,
+    Alphabetical = 1,
+    /// The order set by moving the items around.
+    Custom = 2,
+}
+
+impl<M> Collection<M> {
+    /// Whether the items of this collection are selected by a library filter.
+    pub fn is_smart(&self) -> bool {
+        self.metadata.smart.unwrap_or_default()
+    }
+
+    fn path(&self) -> String {
+        format!("{SERVER_COLLECTIONS}/{}", self.metadata.rating_key)
+    }
+
+    async fn set_pref(&self, name: &str, value: i8) -> Result {
+        self.client
+            .put(format!(
+                "/library/metadata/{}/prefs?{name}={value}",
+                self.metadata.rating_key
+            ))
+            .consume()
+            .await
+    }
+
+    /// Changes how this collection is shown in its library.
+    #[tracing::instrument(level = "debug", skip(self), fields(collection.id = self.rating_key()))]
+    pub async fn set_mode(&self, mode: CollectionMode) -> Result {
+        self.set_pref("collectionMode", mode as i8).await
+    }
+
+    /// Changes the order of the items in this collection.
+    #[tracing::instrument(level = "debug", skip(self), fields(collection.id = self.rating_key()))]
+    pub async fn set_sort(&self, sort: CollectionSort) -> Result {
+        self.set_pref("collectionSort", sort as i8).await
+    }
+
+    /// Sets the poster of this collection to the image at the URL.
+    #[tracing::instrument(level = "debug", skip(self), fields(collection.id = self.rating_key()))]
+    pub async fn set_poster(&self, url: &str) -> Result {
+        artwork::upload_artwork_url(&self.client, &self.metadata, ArtworkKind::Poster, url).await
+    }
+
+    /// Uploads an image to use as the poster of this collection.
+    #[tracing::instrument(level = "debug", skip_all, fields(collection.id = self.rating_key()))]
+    pub async fn upload_poster<R>(&self, image: R) -> Result
+    where
+        R: AsyncRead + Send + Sync + 'static,
+    {
+        artwork::upload_artwork(&self.client, &self.metadata, ArtworkKind::Poster, image).await
+    }
+
+    /// Deletes this collection. The items in it are kept.
+    #[tracing::instrument(level = "debug", skip_all, fields(collection.id = self.rating_key()))]
+    pub async fn delete(self) -> Result {
+        self.client.delete(self.path()).consume().await
+    }
+}
+
+/// The kinds of items a collection can be made of.
+pub trait CollectionContent: FromMetadata + MetadataItem {
+    /// The type of the items used by the server.
+    const SEARCH_TYPE: SearchType;
+}
+
+impl CollectionContent for Movie {
+    const SEARCH_TYPE: SearchType = SearchType::Movie;
+}
+
+impl CollectionContent for Show {
+    const SEARCH_TYPE: SearchType = SearchType::Show;
+}
+
+/// Libraries that can contain collections, e.g. a [`MovieLibrary`] can
+/// contain collections of movies.
+pub trait CollectionLibrary {
+    type Item: CollectionContent;
+
+    /// Returns the unique ID of this library.
+    fn id(&self) -> &str;
+}
+
+impl CollectionLibrary for MovieLibrary {
+    type Item = Movie;
+
+    fn id(&self) -> &str {
+        &self.directory.id
+    }
+}
+
+impl CollectionLibrary for TVLibrary {
+    type Item = Show;
+
+    fn id(&self) -> &str {
+        &self.directory.id
+    }
+}
+
+/// Creates a collection of the items identified by the uri.
+#[tracing::instrument(level = "trace", skip(client))]
+pub(crate) async fn create_collection<M>(
+    client: &HttpClient,
+    section_id: &str,
+    title: &str,
+    smart: bool,
+    uri: &str,
+) -> Result<Collection<M>>
+where
+    M: CollectionContent,
+{
+    let query = serde_urlencoded::to_string([
+        ("type", M::SEARCH_TYPE.to_string().as_str()),
+        ("title", title),
+        ("smart", if smart { "1" } else { "0" }),
+        ("sectionId", section_id),
+        ("uri", uri),
+    ])?;
+
+    let wrapper: MediaContainerWrapper<MetadataMediaContainer> = client
+        .post(format!("{SERVER_COLLECTIONS}?{query}"))
+        .json()
+        .await?;
+
+    wrapper
+        .media_container
+        .metadata
+        .into_iter()
+        .next()
+        .map(|metadata| Collection::from_metadata(client.clone(), metadata))
+        .ok_or(Error::ItemNotFound)
+}
+
 #[derive(Debug, Clone)]
 pub struct Movie {
     client: HttpClient,
@@ -925,17 +1477,55 @@ impl MovieLibrary {
         pivot_items(&self.client, &self.directory, "content.library").await
     }
 
+    /// Streams all of the movies in this library, retrieving them from the
+    /// server a page at a time.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn movies_stream(&self, options: PagingOptions) -> Result<ItemStream<Movie>> {
+        pivot_items_stream(&self.client, &self.directory, "content.library", options).await
+    }
+
     /// Retrieves all of the collections in this library.
     #[tracing::instrument(level = "debug", skip_all)]
     pub async fn collections(&self) -> Result<Vec<Collection<Movie>>> {
         pivot_items(&self.client, &self.directory, "content.collections").await
     }
 
+    /// Streams all of the collections in this library, retrieving them from
+    /// the server a page at a time.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn collections_stream(
+        &self,
+        options: PagingOptions,
+    ) -> Result<ItemStream<Collection<Movie>>> {
+        pivot_items_stream(
+            &self.client,
+            &self.directory,
+            "content.collections",
+            options,
+        )
+        .await
+    }
+
     /// Retrieves all of the playlists containing movies from this library.
     #[tracing::instrument(level = "debug", skip_all)]
     pub async fn playlists(&self) -> Result<Vec<Playlist<Video>>> {
         pivot_items(&self.client, &self.directory, "content.playlists").await
     }
+
+    /// Streams all of the playlists containing movies from this library,
+    /// retrieving them from the server a page at a time.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn playlists_stream(
+        &self,
+        options: PagingOptions,
+    ) -> Result<ItemStream<Playlist<Video>>> {
+        pivot_items_stream(&self.client, &self.directory, "content.playlists", options).await
+    }
+
+    /// Starts a search for movies in this library.
+    pub fn search(&self) -> LibrarySearch<Movie> {
+        LibrarySearch::new(self.client.clone(), &self.directory.id, SearchType::Movie)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -956,17 +1546,65 @@ impl TVLibrary {
         pivot_items(&self.client, &self.directory, "content.library").await
     }
 
+    /// Streams all of the shows in this library, retrieving them from the
+    /// server a page at a time.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn shows_stream(&self, options: PagingOptions) -> Result<ItemStream<Show>> {
+        pivot_items_stream(&self.client, &self.directory, "content.library", options).await
+    }
+
     /// Retrieves all of the collections in this library.
     #[tracing::instrument(level = "debug", skip_all)]
     pub async fn collections(&self) -> Result<Vec<Collection<Show>>> {
         pivot_items(&self.client, &self.directory, "content.collections").await
     }
 
+    /// Streams all of the collections in this library, retrieving them from
+    /// the server a page at a time.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn collections_stream(
+        &self,
+        options: PagingOptions,
+    ) -> Result<ItemStream<Collection<Show>>> {
+        pivot_items_stream(
+            &self.client,
+            &self.directory,
+            "content.collections",
+            options,
+        )
+        .await
+    }
+
     /// Retrieves all of the playlists containing episodes from this library.
     #[tracing::instrument(level = "debug", skip_all)]
     pub async fn playlists(&self) -> Result<Vec<Playlist<Video>>> {
         pivot_items(&self.client, &self.directory, "content.playlists").await
     }
+
+    /// Streams all of the playlists containing episodes from this library,
+    /// retrieving them from the server a page at a time.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn playlists_stream(
+        &self,
+        options: PagingOptions,
+    ) -> Result<ItemStream<Playlist<Video>>> {
+        pivot_items_stream(&self.client, &self.directory, "content.playlists", options).await
+    }
+
+    /// Starts a search for shows in this library.
+    pub fn search_shows(&self) -> LibrarySearch<Show> {
+        LibrarySearch::new(self.client.clone(), &self.directory.id, SearchType::Show)
+    }
+
+    /// Starts a search for seasons in this library.
+    pub fn search_seasons(&self) -> LibrarySearch<Season> {
+        LibrarySearch::new(self.client.clone(), &self.directory.id, SearchType::Season)
+    }
+
+    /// Starts a search for episodes in this library.
+    pub fn search_episodes(&self) -> LibrarySearch<Episode> {
+        LibrarySearch::new(self.client.clone(), &self.directory.id, SearchType::Episode)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -987,11 +1625,43 @@ impl MusicLibrary {
         pivot_items(&self.client, &self.directory, "content.library").await
     }
 
+    /// Streams all of the artists in this library, retrieving them from the
+    /// server a page at a time.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn artists_stream(&self, options: PagingOptions) -> Result<ItemStream<Artist>> {
+        pivot_items_stream(&self.client, &self.directory, "content.library", options).await
+    }
+
     /// Retrieves all of the playlists containing tracks from this library.
     #[tracing::instrument(level = "debug", skip_all)]
     pub async fn playlists(&self) -> Result<Vec<Playlist<Track>>> {
         pivot_items(&self.client, &self.directory, "content.playlists").await
     }
+
+    /// Streams all of the playlists containing tracks from this library,
+    /// retrieving them from the server a page at a time.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn playlists_stream(
+        &self,
+        options: PagingOptions,
+    ) -> Result<ItemStream<Playlist<Track>>> {
+        pivot_items_stream(&self.client, &self.directory, "content.playlists", options).await
+    }
+
+    /// Starts a search for artists in this library.
+    pub fn search_artists(&self) -> LibrarySearch<Artist> {
+        LibrarySearch::new(self.client.clone(), &self.directory.id, SearchType::Artist)
+    }
+
+    /// Starts a search for albums in this library.
+    pub fn search_albums(&self) -> LibrarySearch<MusicAlbum> {
+        LibrarySearch::new(self.client.clone(), &self.directory.id, SearchType::Album)
+    }
+
+    /// Starts a search for tracks in this library.
+    pub fn search_tracks(&self) -> LibrarySearch<Track> {
+        LibrarySearch::new(self.client.clone(), &self.directory.id, SearchType::Track)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1012,11 +1682,42 @@ impl PhotoLibrary {
         pivot_items(&self.client, &self.directory, "content.library").await
     }
 
+    /// Streams all of the albums in this library, retrieving them from the
+    /// server a page at a time.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn albums_stream(&self, options: PagingOptions) -> Result<ItemStream<PhotoAlbum>> {
+        pivot_items_stream(&self.client, &self.directory, "content.library", options).await
+    }
+
     /// Retrieves all of the playlists containing photos from this library.
     #[tracing::instrument(level = "debug", skip_all)]
     pub async fn playlists(&self) -> Result<Vec<Playlist<Photo>>> {
         pivot_items(&self.client, &self.directory, "content.playlists").await
     }
+
+    /// Streams all of the playlists containing photos from this library,
+    /// retrieving them from the server a page at a time.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn playlists_stream(
+        &self,
+        options: PagingOptions,
+    ) -> Result<ItemStream<Playlist<Photo>>> {
+        pivot_items_stream(&self.client, &self.directory, "content.playlists", options).await
+    }
+
+    /// Starts a search for photo albums in this library.
+    pub fn search_albums(&self) -> LibrarySearch<PhotoAlbum> {
+        LibrarySearch::new(
+            self.client.clone(),
+            &self.directory.id,
+            SearchType::PhotoAlbum,
+        )
+    }
+
+    /// Starts a search for photos in this library.
+    pub fn search_photos(&self) -> LibrarySearch<Photo> {
+        LibrarySearch::new(self.client.clone(), &self.directory.id, SearchType::Photo)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1071,4 +1772,173 @@ impl Library {
     pub fn library_type(&self) -> &LibraryType {
         &self.directory().library_type
     }
+
+    fn client(&self) -> &HttpClient {
+        match self {
+            Self::Movie(l) => &l.client,
+            Self::TV(l) => &l.client,
+            Self::Music(l) => &l.client,
+            Self::Video(l) => &l.client,
+            Self::Photo(l) => &l.client,
+        }
+    }
+
+    /// Retrieves the hubs for this library.
+    #[tracing::instrument(level = "debug", skip_all, fields(library.id = self.id()))]
+    pub async fn hubs(&self) -> Result<Vec<Hub>> {
+        hubs(self.client(), &self.directory().hub_key).await
+    }
+
+    /// Retrieves the items that are On Deck in this library.
+    #[tracing::instrument(level = "debug", skip_all, fields(library.id = self.id()))]
+    pub async fn on_deck(&self) -> Result<Vec<Item>> {
+        metadata_items(self.client(), &format!("{}/onDeck", self.directory().key)).await
+    }
+
+    /// Retrieves the items recently added to this library.
+    #[tracing::instrument(level = "debug", skip_all, fields(library.id = self.id()))]
+    pub async fn recently_added(&self) -> Result<Vec<Item>> {
+        metadata_items(
+            self.client(),
+            &format!("{}/recentlyAdded", self.directory().key),
+        )
+        .await
+    }
+
+    /// Searches this library, returning the results grouped into hubs by
+    /// type. `limit` restricts the number of items in each hub.
+    #[tracing::instrument(level = "debug", skip(self), fields(library.id = self.id()))]
+    pub async fn search_hubs(&self, query: &str, limit: Option<u32>) -> Result<Vec<Hub>> {
+        let mut search = Query::new()
+            .param("query", query)
+            .param("sectionId", self.id());
+        if let Some(limit) = limit {
+            search = search.param("limit", limit.to_string());
+        }
+
+        hubs(self.client(), &format!("{SERVER_HUBS_SEARCH}?{search}")).await
+    }
+
+    /// Starts a search for any type of items in this library. Use the
+    /// library specific methods to get typed results.
+    pub fn search(&self, search_type: SearchType) -> LibrarySearch<Item> {
+        LibrarySearch::new(self.client().clone(), self.id(), search_type)
+    }
+
+    /// Retrieves the folders this library is made of.
+    #[tracing::instrument(level = "debug", skip_all, fields(library.id = self.id()))]
+    pub async fn locations(&self) -> Result<Vec<Location>> {
+        library_sections(self.client())
+            .await?
+            .into_iter()
+            .find(|section| section.key == self.id())
+            .map(|section| section.locations)
+            .ok_or(Error::ItemNotFound)
+    }
+
+    /// Replaces the folders this library is made of, at least one is
+    /// required.
+    #[tracing::instrument(level = "debug", skip(self), fields(library.id = self.id()))]
+    pub async fn set_locations(&self, locations: &[&str]) -> Result {
+        if locations.is_empty() {
+            return Err(Error::NoItemsProvided);
+        }
+
+        let mut params = vec![("agent", self.directory().agent.as_str())];
+        params.extend(locations.iter().map(|location| ("location", *location)));
+
+        self.client()
+            .put(format!(
+                "{}?{}",
+                self.directory().key,
+                serde_urlencoded::to_string(params)?
+            ))
+            .consume()
+            .await
+    }
+
+    /// Adds a folder to this library. Nothing is done if the library already
+    /// includes it.
+    #[tracing::instrument(level = "debug", skip(self), fields(library.id = self.id()))]
+    pub async fn add_location(&self, path: &str) -> Result {
+        let locations = self.locations().await?;
+        if locations.iter().any(|location| location.path == path) {
+            return Ok(());
+        }
+
+        let mut paths: Vec<&str> = locations.iter().map(|l| l.path.as_str()).collect();
+        paths.push(path);
+        self.set_locations(&paths).await
+    }
+
+    /// Removes a folder from this library. Nothing is done if the library
+    /// doesn't include it, the last folder can't be removed.
+    #[tracing::instrument(level = "debug", skip(self), fields(library.id = self.id()))]
+    pub async fn remove_location(&self, path: &str) -> Result {
+        let locations = self.locations().await?;
+        if !locations.iter().any(|location| location.path == path) {
+            return Ok(());
+        }
+
+        let paths: Vec<&str> = locations
+            .iter()
+            .map(|l| l.path.as_str())
+            .filter(|p| *p != path)
+            .collect();
+        self.set_locations(&paths).await
+    }
+
+    /// Retrieves the preferences of this library, e.g. whether trailers are
+    /// shown before movies.
+    #[tracing::instrument(level = "debug", skip_all, fields(library.id = self.id()))]
+    pub async fn preferences<'a>(&self) -> Result<Preferences<'a>> {
+        Preferences::with_path(
+            self.client().clone(),
+            format!("{}/prefs", self.directory().key),
+        )
+        .await
+    }
+
+    /// Deletes this library from the server. The media files are kept.
+    #[tracing::instrument(level = "debug", skip_all, fields(library.id = self.id()))]
+    pub async fn delete(self) -> Result {
+        self.client()
+            .delete(self.directory().key.as_str())
+            .consume()
+            .await
+    }
+
+    /// Scans the library for new, changed and removed files. `path` limits
+    /// the scan to a single directory within the library.
+    #[tracing::instrument(level = "debug", skip(self), fields(library.id = self.id()))]
+    pub async fn scan(&self, path: Option<&str>) -> Result<ActivityHandle> {
+        let mut url = format!("{}/refresh", self.directory().key);
+        if let Some(path) = path {
+            url = format!("{url}?{}", serde_urlencoded::to_string([("path", path)])?);
+        }
+
+        start_activity(self.client(), self.client().get(url), Some(self.id())).await
+    }
+
+    /// Refreshes the metadata of all items in the library, even if it was
+    /// already retrieved from the agents.
+    #[tracing::instrument(level = "debug", skip_all, fields(library.id = self.id()))]
+    pub async fn refresh_metadata(&self) -> Result<ActivityHandle> {
+        let url = format!("{}/refresh?force=1", self.directory().key);
+        start_activity(self.client(), self.client().get(url), Some(self.id())).await
+    }
+
+    /// Analyzes the media files of the items in the library.
+    #[tracing::instrument(level = "debug", skip_all, fields(library.id = self.id()))]
+    pub async fn analyze(&self) -> Result<ActivityHandle> {
+        let url = format!("{}/analyze", self.directory().key);
+        start_activity(self.client(), self.client().put(url), Some(self.id())).await
+    }
+
+    /// Removes the items whose files are no longer available from the library.
+    #[tracing::instrument(level = "debug", skip_all, fields(library.id = self.id()))]
+    pub async fn empty_trash(&self) -> Result<ActivityHandle> {
+        let url = format!("{}/emptyTrash", self.directory().key);
+        start_activity(self.client(), self.client().put(url), Some(self.id())).await
+    }
 }
@@ -0,0 +1,112 @@
+use futures::AsyncRead;
+use http::StatusCode;
+use isahc::{AsyncBody, AsyncReadResponseExt};
+
+use crate::{
+    media_container::{
+        server::library::{Artwork, ArtworkMediaContainer, Metadata},
+        MediaContainerWrapper,
+    },
+    Error, HttpClient, Result,
+};
+
+/// The kinds of artwork an item can have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtworkKind {
+    Poster,
+    /// The background image, called art by the server.
+    Background,
+    /// The theme music played while browsing the item.
+    Theme,
+}
+
+impl ArtworkKind {
+    /// The path listing the available artwork, which also accepts uploads.
+    fn list_path(&self, metadata: &Metadata) -> String {
+        let list = match self {
+            ArtworkKind::Poster => "posters",
+            ArtworkKind::Background => "arts",
+            ArtworkKind::Theme => "themes",
+        };
+        format!("/library/metadata/{}/{list}", metadata.rating_key)
+    }
+
+    /// The path selecting one of the available artwork.
+    fn select_path(&self, metadata: &Metadata) -> String {
+        let select = match self {
+            ArtworkKind::Poster => "poster",
+            ArtworkKind::Background => "art",
+            ArtworkKind::Theme => "theme",
+        };
+        format!("/library/metadata/{}/{select}", metadata.rating_key)
+    }
+}
+
+#[tracing::instrument(level = "debug", skip(client, metadata), fields(item.rating_key = metadata.rating_key))]
+pub(crate) async fn artwork(
+    client: &HttpClient,
+    metadata: &Metadata,
+    kind: ArtworkKind,
+) -> Result<Vec<Artwork>> {
+    let wrapper: MediaContainerWrapper<ArtworkMediaContainer> =
+        client.get(kind.list_path(metadata)).json().await?;
+    Ok(wrapper.media_container.artwork)
+}
+
+#[tracing::instrument(level = "debug", skip(client, metadata, artwork), fields(item.rating_key = metadata.rating_key))]
+pub(crate) async fn select_artwork(
+    client: &HttpClient,
+    metadata: &Metadata,
+    kind: ArtworkKind,
+    artwork: &Artwork,
+) -> Result {
+    client
+        .put(format!(
+            "{}?{}",
+            kind.select_path(metadata),
+            serde_urlencoded::to_string([("url", &artwork.rating_key)])?
+        ))
+        .consume()
+        .await
+}
+
+#[tracing::instrument(level = "debug", skip(client, metadata), fields(item.rating_key = metadata.rating_key))]
+pub(crate) async fn upload_artwork_url(
+    client: &HttpClient,
+    metadata: &Metadata,
+    kind: ArtworkKind,
+    url: &str,
+) -> Result {
+    client
+        .post(format!(
+            "{}?{}",
+            kind.list_path(metadata),
+            serde_urlencoded::to_string([("url", url)])?
+        ))
+        .consume()
+        .await
+}
+
+#[tracing::instrument(level = "debug", skip(client, metadata, data), fields(item.rating_key = metadata.rating_key))]
+pub(crate) async fn upload_artwork<R>(
+    client: &HttpClient,
+    metadata: &Metadata,
+    kind: ArtworkKind,
+    data: R,
+) -> Result
+where
+    R: AsyncRead + Send + Sync + 'static,
+{
+    let mut response = client
+        .post(kind.list_path(metadata))
+        .body(AsyncBody::from_reader(data))?
+        .send()
+        .await?;
+
+    if response.status() == StatusCode::OK {
+        response.consume().await?;
+        Ok(())
+    } else {
+        Err(Error::from_response(response).await)
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use time::Date;
+
+use crate::{media_container::server::library::Metadata, Error, HttpClient, Result};
+
+use super::{metadata_items, FromMetadata};
+
+/// Fields of an item that hold a list of tags rather than a single value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TagField {
+    Genre,
+    Label,
+    Collection,
+    Director,
+    Writer,
+    Producer,
+    Country,
+    Mood,
+    Style,
+}
+
+impl TagField {
+    /// The name of the field used by the server, which is also the one to
+    /// pass to [`MetadataEdit::lock`] and [`MetadataEdit::unlock`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            TagField::Genre => "genre",
+            TagField::Label => "label",
+            TagField::Collection => "collection",
+            TagField::Director => "director",
+            TagField::Writer => "writer",
+            TagField::Producer => "producer",
+            TagField::Country => "country",
+            TagField::Mood => "mood",
+            TagField::Style => "style",
+        }
+    }
+}
+
+/// A set of changes to the metadata of an item.
+///
+/// Nothing is sent to the server until [`MetadataEdit::apply`] is called.
+/// Like in Plex Web, every changed field gets locked so that refreshing the
+/// metadata from the agents doesn't overwrite the change. Use
+/// [`MetadataEdit::unlock`] to allow the agents to update a field again.
+#[derive(Debug, Clone)]
+pub struct MetadataEdit<'a> {
+    client: &'a HttpClient,
+    metadata: &'a Metadata,
+    params: Vec<(String, String)>,
+    /// The number of tags added to each field so far, the server expects
+    /// them to be indexed.
+    added_tags: HashMap<TagField, usize>,
+}
+
+impl<'a> MetadataEdit<'a> {
+    pub(crate) fn new(client: &'a HttpClient, metadata: &'a Metadata) -> Self {
+        Self {
+            client,
+            metadata,
+            params: Vec::new(),
+            added_tags: HashMap::new(),
+        }
+    }
+
+    /// Sets any of the item's fields, e.g. `titleSort`, and locks it.
+    #[must_use]
+    pub fn field<V: ToString>(mut self, name: &str, value: V) -> Self {
+        self.params
+            .push((format!("{name}.value"), value.to_string()));
+        self.lock(name)
+    }
+
+    #[must_use]
+    pub fn title(self, title: &str) -> Self {
+        self.field("title", title)
+    }
+
+    /// The title used for sorting the item instead of the title.
+    #[must_use]
+    pub fn sort_title(self, title: &str) -> Self {
+        self.field("titleSort", title)
+    }
+
+    #[must_use]
+    pub fn original_title(self, title: &str) -> Self {
+        self.field("originalTitle", title)
+    }
+
+    #[must_use]
+    pub fn summary(self, summary: &str) -> Self {
+        self.field("summary", summary)
+    }
+
+    #[must_use]
+    pub fn tagline(self, tagline: &str) -> Self {
+        self.field("tagline", tagline)
+    }
+
+    #[must_use]
+    pub fn studio(self, studio: &str) -> Self {
+        self.field("studio", studio)
+    }
+
+    /// The content rating of the item, e.g. `PG-13`.
+    #[must_use]
+    pub fn content_rating(self, rating: &str) -> Self {
+        self.field("contentRating", rating)
+    }
+
+    #[must_use]
+    pub fn year(self, year: u32) -> Self {
+        self.field("year", year)
+    }
+
+    /// The date the item was first released.
+    #[must_use]
+    pub fn originally_available_at(self, date: Date) -> Self {
+        let date = format!(
+            "{:04}-{:02}-{:02}",
+            date.year(),
+            u8::from(date.month()),
+            date.day()
+        );
+        self.field("originallyAvailableAt", date)
+    }
+
+    /// Adds the tags to the field and locks it. Tags the item already has
+    /// are kept.
+    #[must_use]
+    pub fn add_tags(mut self, field: TagField, tags: &[&str]) -> Self {
+        let added = self.added_tags.entry(field).or_default();
+        for tag in tags {
+            self.params.push((
+                format!("{}[{added}].tag.tag", field.name()),
+                tag.to_string(),
+            ));
+            *added += 1;
+        }
+        self.lock(field.name())
+    }
+
+    /// Removes the tags from the field and locks it.
+    #[must_use]
+    pub fn remove_tags(mut self, field: TagField, tags: &[&str]) -> Self {
+        self.params
+            .push((format!("{}[].tag.tag-", field.name()), tags.join(",")));
+        self.lock(field.name())
+    }
+
+    /// Prevents the agents from changing the field when refreshing the
+    /// metadata.
+    #[must_use]
+    pub fn lock(self, name: &str) -> Self {
+        self.set_locked(name, true)
+    }
+
+    /// Allows the agents to change the field when refreshing the metadata.
+    #[must_use]
+    pub fn unlock(self, name: &str) -> Self {
+        self.set_locked(name, false)
+    }
+
+    fn set_locked(mut self, name: &str, locked: bool) -> Self {
+        let key = format!("{name}.locked");
+        self.params.retain(|(k, _)| *k != key);
+        self.params
+            .push((key, if locked { "1" } else { "0" }.to_owned()));
+        self
+    }
+
+    /// Sends the changes to the server and returns the updated item.
+    #[tracing::instrument(level = "debug", skip_all, fields(item.rating_key = self.metadata.rating_key))]
+    pub async fn apply<M>(self) -> Result<M>
+    where
+        M: FromMetadata,
+    {
+        let path = format!("/library/metadata/{}", self.metadata.rating_key);
+        if !self.params.is_empty() {
+            self.client
+                .put(format!(
+                    "{path}?{}",
+                    serde_urlencoded::to_string(&self.params)?
+                ))
+                .consume()
+                .await?;
+        }
+
+        metadata_items(self.client, &path)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(Error::ItemNotFound)
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{
+    media_container::{
+        server::{
+            library::{Guid, Metadata, SearchResult, SearchResultsMediaContainer},
+            MediaProviderFeature,
+        },
+        MediaContainerWrapper,
+    },
+    Error, HttpClient, Result,
+};
+
+/// A possible match for an item found by
+/// [`MetadataItemWithMatching::search_matches`](super::MetadataItemWithMatching::search_matches).
+#[derive(Debug, Clone)]
+pub struct MatchCandidate {
+    result: SearchResult,
+    guid: Option<Guid>,
+}
+
+impl MatchCandidate {
+    fn new(result: SearchResult) -> Self {
+        let guid = serde_plain::from_str(&result.guid).ok();
+        Self { result, guid }
+    }
+
+    /// The guid of the candidate, if it's in a known format.
+    pub fn guid(&self) -> Option<&Guid> {
+        self.guid.as_ref()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.result.name
+    }
+
+    pub fn year(&self) -> Option<u32> {
+        self.result.year
+    }
+
+    /// How well the candidate matches the search, 100 being the best.
+    pub fn score(&self) -> u32 {
+        self.result.score
+    }
+
+    /// Whether the item is currently matched to this candidate.
+    pub fn is_matched(&self) -> bool {
+        self.result.matched
+    }
+
+    /// The details of the candidate as returned by the server.
+    pub fn result(&self) -> &SearchResult {
+        &self.result
+    }
+}
+
+fn path(metadata: &Metadata, action: &str) -> String {
+    format!("/library/metadata/{}/{action}", metadata.rating_key)
+}
+
+/// The server's support for fixing the match of items, retrieved with
+/// [`Server::match_feature`](crate::Server::match_feature).
+#[derive(Debug, Clone)]
+pub struct MatchFeature {
+    /// The key of the library's `Metadata` feature, e.g. `/library/metadata`.
+    metadata_key: String,
+}
+
+impl MatchFeature {
+    /// Requires the library provider to advertise both the `Match` feature
+    /// and the `Metadata` one the match actions of items live under.
+    pub(crate) fn from_features(features: &[MediaProviderFeature]) -> Result<Self> {
+        let mut advertised = false;
+        let mut metadata_key = None;
+        for feature in features {
+            match feature {
+                MediaProviderFeature::Match { .. } => advertised = true,
+                MediaProviderFeature::Metadata { key } => metadata_key = Some(key.clone()),
+                _ => {}
+            }
+        }
+
+        match metadata_key {
+            Some(metadata_key) if advertised => Ok(Self { metadata_key }),
+            _ => Err(Error::MatchNotSupported),
+        }
+    }
+
+    fn path(&self, metadata: &Metadata, action: &str) -> String {
+        format!("{}/{}/{action}", self.metadata_key, metadata.rating_key)
+    }
+}
+
+#[tracing::instrument(level = "debug", skip(client, feature, metadata), fields(item.rating_key = metadata.rating_key))]
+pub(super) async fn search_matches(
+    client: &HttpClient,
+    feature: &MatchFeature,
+    metadata: &Metadata,
+    title: Option<&str>,
+    year: Option<u32>,
+    agent: Option<&str>,
+) -> Result<Vec<MatchCandidate>> {
+    let year = year.map(|year| year.to_string());
+    let mut params = vec![("manual", "1")];
+    if let Some(title) = title {
+        params.push(("title", title));
+    }
+    if let Some(ref year) = year {
+        params.push(("year", year));
+    }
+    if let Some(agent) = agent {
+        params.push(("agent", agent));
+    }
+
+    let wrapper: MediaContainerWrapper<SearchResultsMediaContainer> = client
+        .get(format!(
+            "{}?{}",
+            feature.path(metadata, "matches"),
+            serde_urlencoded::to_string(params)?
+        ))
+        .json()
+        .await?;
+
+    Ok(wrapper
+        .media_container
+        .results
+        .into_iter()
+        .map(MatchCandidate::new)
+        .collect())
+}
+
+#[tracing::instrument(level = "debug", skip_all, fields(item.rating_key = metadata.rating_key))]
+pub(super) async fn apply_match(
+    client: &HttpClient,
+    feature: &MatchFeature,
+    metadata: &Metadata,
+    candidate: &MatchCandidate,
+) -> Result {
+    let mut params = vec![
+        ("guid", candidate.result.guid.clone()),
+        ("name", candidate.result.name.clone()),
+    ];
+    if let Some(year) = candidate.result.year {
+        params.push(("year", year.to_string()));
+    }
+
+    client
+        .put(format!(
+            "{}?{}",
+            feature.path(metadata, "match"),
+            serde_urlencoded::to_string(params)?
+        ))
+        .consume()
+        .await
+}
+
+#[tracing::instrument(level = "debug", skip_all, fields(item.rating_key = metadata.rating_key))]
+pub(super) async fn unmatch(
+    client: &HttpClient,
+    feature: &MatchFeature,
+    metadata: &Metadata,
+) -> Result {
+    client
+        .put(feature.path(metadata, "unmatch"))
+        .consume()
+        .await
+}
+
+#[tracing::instrument(level = "debug", skip_all, fields(item.rating_key = metadata.rating_key))]
+pub(super) async fn refresh_metadata(client: &HttpClient, metadata: &Metadata) -> Result {
+    client
+        .put(format!("{}?force=1", path(metadata, "refresh")))
+        .consume()
+        .await
+}
@@ -0,0 +1,113 @@
+use std::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{
+    stream::{self, BoxStream},
+    Stream, StreamExt, TryStreamExt,
+};
+
+use crate::{Error, HttpClient, Result};
+
+use super::{metadata_page, FromMetadata};
+
+/// Controls how large lists of items are retrieved from the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PagingOptions {
+    /// The number of items requested from the server at once.
+    pub page_size: u32,
+    /// The number of pages that can be requested concurrently while the
+    /// stream is being consumed.
+    pub prefetch: usize,
+}
+
+impl Default for PagingOptions {
+    fn default() -> Self {
+        Self {
+            page_size: 100,
+            prefetch: 2,
+        }
+    }
+}
+
+/// A stream of items retrieved from the server page by page.
+///
+/// The first page is requested when the stream is created, so that the total
+/// number of items is known up front. The remaining pages are only requested
+/// as the stream is polled.
+pub struct ItemStream<T> {
+    total_size: u32,
+    inner: BoxStream<'static, Result<T>>,
+}
+
+impl<T: Send + 'static> ItemStream<T> {
+    pub(crate) fn empty() -> Self {
+        Self {
+            total_size: 0,
+            inner: stream::empty().boxed(),
+        }
+    }
+}
+
+impl<T> ItemStream<T> {
+    /// The total number of items in the list.
+    pub fn total_size(&self) -> u32 {
+        self.total_size
+    }
+}
+
+impl<T> fmt::Debug for ItemStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ItemStream")
+            .field("total_size", &self.total_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Stream for ItemStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+/// Retrieves a list of metadata items given the lookup key, one page at a time.
+#[tracing::instrument(level = "trace", skip(client))]
+pub(crate) async fn metadata_items_stream<T>(
+    client: &HttpClient,
+    path: &str,
+    options: PagingOptions,
+) -> Result<ItemStream<T>>
+where
+    T: FromMetadata + Send + 'static,
+{
+    let page_size = options.page_size.max(1);
+    let (items, total_size) = metadata_page::<T>(client, path, 0, page_size).await?;
+    // Without a total size the server ignored the paging headers and returned
+    // everything at once.
+    let total_size = total_size.unwrap_or(items.len() as u32);
+
+    let client = client.clone();
+    let path = path.to_owned();
+    let remaining = stream::iter((page_size..total_size).step_by(page_size as usize))
+        .map(move |start| {
+            let client = client.clone();
+            let path = path.clone();
+            async move {
+                let (items, _) = metadata_page::<T>(&client, &path, start, page_size).await?;
+                Ok::<_, Error>(stream::iter(items.into_iter().map(Ok)))
+            }
+        })
+        .buffered(options.prefetch.max(1))
+        .try_flatten();
+
+    Ok(ItemStream {
+        total_size,
+        inner: stream::iter(items.into_iter().map(Ok))
+            .chain(remaining)
+            .boxed(),
+    })
+}
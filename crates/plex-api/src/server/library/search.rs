@@ -0,0 +1,385 @@
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+};
+
+use serde::Serialize;
+use serde_plain::derive_display_from_serialize;
+use time::OffsetDateTime;
+
+use crate::{
+    media_container::{
+        server::library::{
+            FilterType, LibraryDirectoryMediaContainer, LibraryFilter, LibraryFilterValue,
+            LibrarySort, SearchType, SortDirection,
+        },
+        MediaContainerWrapper,
+    },
+    Error, HttpClient, Result,
+};
+
+use super::{metadata_items, metadata_items_stream, FromMetadata, ItemStream, PagingOptions};
+
+/// Comparison operators supported by the library filters. Not every operator
+/// makes sense for every [`FilterType`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    /// The item has the tag, or the string field contains the value.
+    Is,
+    /// The opposite of [`Operator::Is`].
+    IsNot,
+    /// The string field is exactly the value.
+    Equals,
+    /// The opposite of [`Operator::Equals`].
+    NotEquals,
+    /// The string field begins with the value.
+    BeginsWith,
+    /// The string field ends with the value.
+    EndsWith,
+    /// The number is greater than the value, or the date is after it.
+    GreaterThan,
+    /// The number is less than the value, or the date is before it.
+    LessThan,
+}
+
+impl Operator {
+    /// The server expects filters in the `{field}{operator}={value}` form,
+    /// e.g. `year>>=1990`, so the trailing `=` isn't part of the suffix.
+    fn suffix(&self) -> &'static str {
+        match self {
+            Operator::Is => "",
+            Operator::IsNot => "!",
+            Operator::Equals => "=",
+            Operator::NotEquals => "!=",
+            Operator::BeginsWith => "<",
+            Operator::EndsWith => ">",
+            Operator::GreaterThan => ">>",
+            Operator::LessThan => "<<",
+        }
+    }
+}
+
+/// Video resolutions accepted by the `resolution` filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Resolution {
+    #[serde(rename = "sd")]
+    Sd,
+    #[serde(rename = "480")]
+    R480,
+    #[serde(rename = "576")]
+    R576,
+    #[serde(rename = "720")]
+    R720,
+    #[serde(rename = "1080")]
+    R1080,
+    #[serde(rename = "4k")]
+    R4k,
+}
+
+derive_display_from_serialize!(Resolution);
+
+#[derive(Debug, Clone)]
+struct Criterion {
+    field: String,
+    operator: Operator,
+    value: String,
+    /// Fields like `addedAt` can be used for filtering, but aren't listed
+    /// among the library filters, so they can't be validated.
+    validate: bool,
+}
+
+/// A search within a library section.
+///
+/// The search is built up by chaining the filters and sort orders, nothing is
+/// sent to the server until [`LibrarySearch::execute`] is called. Before
+/// performing the search the requested filters and sort orders are checked
+/// against the ones supported by the library, and filter values passed by
+/// title (e.g. a genre name) are converted to the keys the server expects.
+#[derive(Debug, Clone)]
+pub struct LibrarySearch<T> {
+    _items: PhantomData<T>,
+    client: HttpClient,
+    section_id: String,
+    search_type: SearchType,
+    criteria: Vec<Criterion>,
+    sorts: Vec<(String, SortDirection)>,
+}
+
+impl<T> LibrarySearch<T> {
+    pub(crate) fn new(client: HttpClient, section_id: &str, search_type: SearchType) -> Self {
+        Self {
+            _items: PhantomData,
+            client,
+            section_id: section_id.to_owned(),
+            search_type,
+            criteria: Vec::new(),
+            sorts: Vec::new(),
+        }
+    }
+
+    pub(crate) fn section_id(&self) -> &str {
+        &self.section_id
+    }
+
+    /// The type of the items this search returns.
+    pub fn search_type(&self) -> SearchType {
+        self.search_type
+    }
+
+    /// Adds a filter by any of the fields returned by [`LibrarySearch::filters`].
+    /// Multiple filters are combined using AND.
+    #[must_use]
+    pub fn filter<F, V>(mut self, field: F, operator: Operator, value: V) -> Self
+    where
+        F: Into<String>,
+        V: ToString,
+    {
+        self.criteria.push(Criterion {
+            field: field.into(),
+            operator,
+            value: value.to_string(),
+            validate: true,
+        });
+        self
+    }
+
+    /// Only include items with the genre. Either the genre name or its key
+    /// can be used.
+    #[must_use]
+    pub fn genre<V: ToString>(self, genre: V) -> Self {
+        self.filter("genre", Operator::Is, genre)
+    }
+
+    /// Only include items with the label. Either the label name or its key
+    /// can be used.
+    #[must_use]
+    pub fn label<V: ToString>(self, label: V) -> Self {
+        self.filter("label", Operator::Is, label)
+    }
+
+    /// Only include items with the content rating, e.g. `PG-13`.
+    #[must_use]
+    pub fn content_rating<V: ToString>(self, content_rating: V) -> Self {
+        self.filter("contentRating", Operator::Is, content_rating)
+    }
+
+    /// Only include items available in the resolution.
+    #[must_use]
+    pub fn resolution(self, resolution: Resolution) -> Self {
+        self.filter("resolution", Operator::Is, resolution)
+    }
+
+    /// Only include items that weren't fully watched yet.
+    #[must_use]
+    pub fn unwatched(self) -> Self {
+        self.filter("unwatched", Operator::Is, "1")
+    }
+
+    /// Only include items released within the range of years.
+    #[must_use]
+    pub fn year<R: RangeBounds<u32>>(mut self, years: R) -> Self {
+        match years.start_bound() {
+            Bound::Included(v) => {
+                self = self.filter("year", Operator::GreaterThan, v.saturating_sub(1))
+            }
+            Bound::Excluded(v) => self = self.filter("year", Operator::GreaterThan, v),
+            Bound::Unbounded => {}
+        }
+
+        match years.end_bound() {
+            Bound::Included(v) => {
+                self = self.filter("year", Operator::LessThan, v.saturating_add(1))
+            }
+            Bound::Excluded(v) => self = self.filter("year", Operator::LessThan, v),
+            Bound::Unbounded => {}
+        }
+
+        self
+    }
+
+    /// Only include items added to the library within the range of time.
+    #[must_use]
+    pub fn added_at<R: RangeBounds<OffsetDateTime>>(mut self, added_at: R) -> Self {
+        let mut push = |operator, value: i64| {
+            self.criteria.push(Criterion {
+                field: "addedAt".to_owned(),
+                operator,
+                value: value.to_string(),
+                validate: false,
+            })
+        };
+
+        match added_at.start_bound() {
+            Bound::Included(v) => push(Operator::GreaterThan, v.unix_timestamp() - 1),
+            Bound::Excluded(v) => push(Operator::GreaterThan, v.unix_timestamp()),
+            Bound::Unbounded => {}
+        }
+
+        match added_at.end_bound() {
+            Bound::Included(v) => push(Operator::LessThan, v.unix_timestamp() + 1),
+            Bound::Excluded(v) => push(Operator::LessThan, v.unix_timestamp()),
+            Bound::Unbounded => {}
+        }
+
+        self
+    }
+
+    /// Sorts the results by any of the fields returned by [`LibrarySearch::sorts`].
+    /// Calling this multiple times sorts by multiple fields, in the order of
+    /// the calls.
+    #[must_use]
+    pub fn sort<F: Into<String>>(mut self, field: F, direction: SortDirection) -> Self {
+        self.sorts.push((field.into(), direction));
+        self
+    }
+
+    /// Retrieves the filters the library supports for this type of items.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn filters(&self) -> Result<Vec<LibraryFilter>> {
+        let path = format!(
+            "/library/sections/{}/filters?type={}",
+            self.section_id, self.search_type
+        );
+        let wrapper: MediaContainerWrapper<LibraryDirectoryMediaContainer<LibraryFilter>> =
+            self.client.get(path).json().await?;
+        Ok(wrapper.media_container.directories)
+    }
+
+    /// Retrieves the sort orders the library supports for this type of items.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn sorts(&self) -> Result<Vec<LibrarySort>> {
+        let path = format!(
+            "/library/sections/{}/sorts?type={}",
+            self.section_id, self.search_type
+        );
+        let wrapper: MediaContainerWrapper<LibraryDirectoryMediaContainer<LibrarySort>> =
+            self.client.get(path).json().await?;
+        Ok(wrapper.media_container.directories)
+    }
+
+    /// Retrieves the possible values of the filter for this type of items.
+    #[tracing::instrument(level = "debug", skip(self, filter), fields(filter.key = filter.key))]
+    pub async fn filter_values(&self, filter: &LibraryFilter) -> Result<Vec<LibraryFilterValue>> {
+        let separator = if filter.key.contains('?') { '&' } else { '?' };
+        let path = format!("{}{separator}type={}", filter.key, self.search_type);
+        let wrapper: MediaContainerWrapper<LibraryDirectoryMediaContainer<LibraryFilterValue>> =
+            self.client.get(path).json().await?;
+        Ok(wrapper.media_container.directories)
+    }
+
+    /// Checks the search against the filters and sorts supported by the
+    /// library, returning the criteria with any values converted to the keys
+    /// expected by the server.
+    async fn resolve(&self) -> Result<Vec<Criterion>> {
+        let mut criteria = self.criteria.clone();
+
+        if criteria.iter().any(|c| c.validate) {
+            let filters = self.filters().await?;
+            let mut values: HashMap<String, Vec<LibraryFilterValue>> = HashMap::new();
+
+            for criterion in criteria.iter_mut().filter(|c| c.validate) {
+                let filter = filters
+                    .iter()
+                    .find(|f| f.filter == criterion.field)
+                    .ok_or_else(|| Error::SearchFilterNotSupported(criterion.field.clone()))?;
+
+                // Only tag-like filters have a fixed list of possible values.
+                if filter.filter_type != FilterType::String
+                    || !matches!(criterion.operator, Operator::Is | Operator::IsNot)
+                {
+                    continue;
+                }
+
+                if !values.contains_key(&filter.filter) {
+                    values.insert(filter.filter.clone(), self.filter_values(filter).await?);
+                }
+
+                let value = values[&filter.filter]
+                    .iter()
+                    .find(|v| v.key == criterion.value)
+                    .or_else(|| {
+                        values[&filter.filter]
+                            .iter()
+                            .find(|v| v.title.eq_ignore_ascii_case(&criterion.value))
+                    })
+                    .ok_or_else(|| Error::SearchFilterValueNotFound {
+                        filter: criterion.field.clone(),
+                        value: criterion.value.clone(),
+                    })?;
+                criterion.value.clone_from(&value.key);
+            }
+        }
+
+        if !self.sorts.is_empty() {
+            let sorts = self.sorts().await?;
+            if let Some((field, _)) = self
+                .sorts
+                .iter()
+                .find(|(field, _)| !sorts.iter().any(|s| &s.key == field))
+            {
+                return Err(Error::SearchSortNotSupported(field.clone()));
+            }
+        }
+
+        Ok(criteria)
+    }
+
+    /// Validates the search and builds the path that performs it.
+    pub(crate) async fn path(&self) -> Result<String> {
+        let criteria = self.resolve().await?;
+        Ok(format!(
+            "/library/sections/{}/all?{}",
+            self.section_id,
+            self.query(&criteria)?
+        ))
+    }
+
+    fn query(&self, criteria: &[Criterion]) -> Result<String> {
+        let mut params = vec![("type".to_owned(), self.search_type.to_string())];
+
+        params.extend(criteria.iter().map(|c| {
+            (
+                format!("{}{}", c.field, c.operator.suffix()),
+                c.value.clone(),
+            )
+        }));
+
+        if !self.sorts.is_empty() {
+            let sort = self
+                .sorts
+                .iter()
+                .map(|(field, direction)| format!("{field}:{direction}"))
+                .collect::<Vec<_>>()
+                .join(",");
+            params.push(("sort".to_owned(), sort));
+        }
+
+        Ok(serde_urlencoded::to_string(params)?)
+    }
+}
+
+impl<T> LibrarySearch<T>
+where
+    T: FromMetadata,
+{
+    /// Validates the search and retrieves all of the matching items.
+    #[tracing::instrument(level = "debug", skip_all, fields(section_id = self.section_id))]
+    pub async fn execute(&self) -> Result<Vec<T>> {
+        let path = self.path().await?;
+        metadata_items(&self.client, &path).await
+    }
+}
+
+impl<T> LibrarySearch<T>
+where
+    T: FromMetadata + Send + 'static,
+{
+    /// Validates the search and streams the matching items, retrieving them
+    /// from the server a page at a time.
+    #[tracing::instrument(level = "debug", skip_all, fields(section_id = self.section_id))]
+    pub async fn stream(&self, options: PagingOptions) -> Result<ItemStream<T>> {
+        let path = self.path().await?;
+        metadata_items_stream(&self.client, &path, options).await
+    }
+}
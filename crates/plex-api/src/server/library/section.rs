@@ -0,0 +1,76 @@
+use crate::{
+    media_container::{
+        server::library::{
+            LibraryDirectoryMediaContainer, LibrarySection, LibraryType, SearchType,
+        },
+        MediaContainerWrapper,
+    },
+    url::SERVER_LIBRARY_SECTIONS,
+    HttpClient, Result,
+};
+
+/// The settings of a library section to create, see [`Server::agents`] and
+/// [`Server::scanners`] for the possible agents and scanners.
+///
+/// [`Server::agents`]: crate::Server::agents
+/// [`Server::scanners`]: crate::Server::scanners
+#[derive(Debug, Clone)]
+pub struct NewLibrary {
+    pub name: String,
+    pub library_type: LibraryType,
+    /// The metadata agent, e.g. `tv.plex.agents.movie`.
+    pub agent: String,
+    /// The scanner, e.g. `Plex Movie`.
+    pub scanner: String,
+    /// The language of the metadata, e.g. `en-US`.
+    pub language: String,
+    /// The folders containing the media, at least one is required.
+    pub locations: Vec<String>,
+    /// Any of the library preferences to set, e.g. `enableCinemaTrailers`.
+    pub preferences: Vec<(String, String)>,
+}
+
+impl NewLibrary {
+    /// The type of items the agents and scanners for this library must
+    /// support. Mixed libraries don't map to a single type.
+    pub(crate) fn media_type(&self) -> Option<SearchType> {
+        match self.library_type {
+            LibraryType::Movie => Some(SearchType::Movie),
+            LibraryType::Show => Some(SearchType::Show),
+            LibraryType::Artist => Some(SearchType::Artist),
+            LibraryType::Photo => Some(SearchType::Photo),
+            LibraryType::Clip => Some(SearchType::Clip),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn query(&self) -> Result<String> {
+        let mut params = vec![
+            ("name".to_owned(), self.name.clone()),
+            ("type".to_owned(), self.library_type.to_string()),
+            ("agent".to_owned(), self.agent.clone()),
+            ("scanner".to_owned(), self.scanner.clone()),
+            ("language".to_owned(), self.language.clone()),
+        ];
+        params.extend(
+            self.locations
+                .iter()
+                .map(|location| ("location".to_owned(), location.clone())),
+        );
+        params.extend(
+            self.preferences
+                .iter()
+                .map(|(key, value)| (format!("prefs[{key}]"), value.clone())),
+        );
+
+        Ok(serde_urlencoded::to_string(params)?)
+    }
+}
+
+/// Retrieves all of the library sections, including their folders.
+#[tracing::instrument(level = "trace", skip(client))]
+pub(crate) async fn library_sections(client: &HttpClient) -> Result<Vec<LibrarySection>> {
+    let wrapper: MediaContainerWrapper<LibraryDirectoryMediaContainer<LibrarySection>> =
+        client.get(SERVER_LIBRARY_SECTIONS).json().await?;
+    Ok(wrapper.media_container.directories)
+}
@@ -0,0 +1,430 @@
+use crate::{
+    media_container::server::library::{AudioStream, Part, Stream, SubtitleStream},
+    myplex::account::{
+        AutoSelectSubtitleMode, DefaultSubtitleAccessibility, DefaultSubtitleForced, Profile,
+    },
+    transcode::{SubtitleSelection, VideoTranscodeOptions},
+    url::SERVER_LIBRARY_PART,
+    Error, HttpClient, Result,
+};
+
+/// Whose playback a stream selection applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StreamSelectionScope {
+    /// Only the user the client is authenticated as.
+    #[default]
+    CurrentUser,
+    /// Every user with access to the server. Only the server owner may do this.
+    AllUsers,
+}
+
+async fn select_stream(
+    client: &HttpClient,
+    part: &Part,
+    param: &str,
+    stream_id: &str,
+    scope: StreamSelectionScope,
+) -> Result {
+    let part_id = part.id.as_ref().ok_or(Error::PartIdNotAvailable)?;
+
+    let mut params = vec![(param, stream_id), ("allParts", "1")];
+    if scope == StreamSelectionScope::AllUsers {
+        params.push(("allUsers", "1"));
+    }
+
+    client
+        .put(format!(
+            "{}?{}",
+            SERVER_LIBRARY_PART.replace("{part_id}", part_id),
+            serde_urlencoded::to_string(params)?
+        ))
+        .consume()
+        .await
+}
+
+#[tracing::instrument(level = "debug", skip(client, part, stream), fields(part.id = part.id, stream.id = stream.id))]
+pub(super) async fn select_audio_stream(
+    client: &HttpClient,
+    part: &Part,
+    stream: &AudioStream,
+    scope: StreamSelectionScope,
+) -> Result {
+    select_stream(client, part, "audioStreamID", &stream.id, scope).await
+}
+
+#[tracing::instrument(level = "debug", skip(client, part), fields(part.id = part.id))]
+pub(super) async fn select_subtitle_stream(
+    client: &HttpClient,
+    part: &Part,
+    selection: &SubtitleSelection,
+    scope: StreamSelectionScope,
+) -> Result {
+    select_stream(
+        client,
+        part,
+        "subtitleStreamID",
+        selection.stream_id(),
+        scope,
+    )
+    .await
+}
+
+/// The audio and subtitle streams the official clients would pick for a part
+/// given an account's language [`Profile`].
+#[derive(Debug, Clone, Copy)]
+pub struct StreamChoice<'a> {
+    /// The audio stream to play, only `None` when the part has no audio.
+    pub audio: Option<&'a AudioStream>,
+    /// The subtitles to show, `None` meaning subtitles are off.
+    pub subtitle: Option<&'a SubtitleStream>,
+}
+
+impl<'a> StreamChoice<'a> {
+    /// Picks the streams from the part following the account's preferences.
+    /// Where the profile leaves the choice to the user the streams currently
+    /// selected on the server are kept.
+    pub fn resolve(part: &'a Part, profile: &Profile) -> Self {
+        let streams = part.streams.iter().flatten();
+        let audio_streams: Vec<&AudioStream> = streams
+            .clone()
+            .filter_map(|stream| match stream {
+                Stream::Audio(stream) => Some(stream),
+                _ => None,
+            })
+            .collect();
+        let subtitle_streams: Vec<&SubtitleStream> = streams
+            .filter_map(|stream| match stream {
+                Stream::Subtitle(stream) => Some(stream),
+                _ => None,
+            })
+            .collect();
+
+        let audio = resolve_audio(&audio_streams, profile);
+        let subtitle = resolve_subtitle(&subtitle_streams, audio, profile);
+
+        Self { audio, subtitle }
+    }
+
+    /// Makes a transcode use the chosen streams.
+    pub fn apply_to(&self, options: &mut VideoTranscodeOptions) {
+        options.audio_stream = self.audio.map(|stream| stream.id.clone());
+        options.subtitle_stream = Some(self.subtitle.into());
+    }
+}
+
+/// The fields shared by audio and subtitle streams that matter when choosing
+/// between them.
+trait LanguageStream {
+    fn language_tag(&self) -> Option<&str>;
+    fn language_code(&self) -> Option<&str>;
+    fn is_selected(&self) -> bool;
+    fn is_default(&self) -> bool;
+
+    /// Whether the stream is in the given language. Profiles use two letter
+    /// codes while streams carry both a tag and a three letter code.
+    fn is_language(&self, language: &str) -> bool {
+        let language = primary_subtag(language);
+        self.language_tag()
+            .map(primary_subtag)
+            .into_iter()
+            .chain(self.language_code())
+            .any(|code| code.eq_ignore_ascii_case(language))
+    }
+
+    fn has_language(&self) -> bool {
+        self.language_tag().is_some() || self.language_code().is_some()
+    }
+}
+
+macro_rules! language_stream {
+    ($type:ty) => {
+        impl LanguageStream for $type {
+            fn language_tag(&self) -> Option<&str> {
+                self.language_tag.as_deref()
+            }
+
+            fn language_code(&self) -> Option<&str> {
+                self.language_code.as_deref()
+            }
+
+            fn is_selected(&self) -> bool {
+                self.selected == Some(true)
+            }
+
+            fn is_default(&self) -> bool {
+                self.default == Some(true)
+            }
+        }
+    };
+}
+
+language_stream!(AudioStream);
+language_stream!(SubtitleStream);
+
+fn primary_subtag(tag: &str) -> &str {
+    tag.split('-').next().unwrap_or(tag)
+}
+
+/// The preferred languages from a profile, falling back from the list to the
+/// single language.
+fn preferred_languages<'p>(
+    list: &'p Option<Vec<String>>,
+    single: &'p Option<String>,
+) -> Vec<&'p str> {
+    match list {
+        Some(list) if !list.is_empty() => list.iter().map(String::as_str).collect(),
+        _ => single.iter().map(String::as_str).collect(),
+    }
+}
+
+/// Prefers the selected stream, then the default one, then the first.
+fn preferred<'a, S: LanguageStream>(streams: &[&'a S]) -> Option<&'a S> {
+    streams
+        .iter()
+        .find(|stream| stream.is_selected())
+        .or_else(|| streams.iter().find(|stream| stream.is_default()))
+        .or_else(|| streams.first())
+        .copied()
+}
+
+fn resolve_audio<'a>(streams: &[&'a AudioStream], profile: &Profile) -> Option<&'a AudioStream> {
+    if profile.auto_select_audio {
+        for language in preferred_languages(
+            &profile.default_audio_languages,
+            &profile.default_audio_language,
+        ) {
+            let matching: Vec<&AudioStream> = streams
+                .iter()
+                .filter(|stream| stream.is_language(language))
+                .copied()
+                .collect();
+            if let Some(stream) = preferred(&matching) {
+                return Some(stream);
+            }
+        }
+    }
+
+    preferred(streams)
+}
+
+fn resolve_subtitle<'a>(
+    streams: &[&'a SubtitleStream],
+    audio: Option<&AudioStream>,
+    profile: &Profile,
+) -> Option<&'a SubtitleStream> {
+    let mut languages = preferred_languages(
+        &profile.default_subtitle_languages,
+        &profile.default_subtitle_language,
+    );
+    if languages.is_empty() {
+        // Without a subtitle language the clients use the audio language.
+        languages = preferred_languages(
+            &profile.default_audio_languages,
+            &profile.default_audio_language,
+        );
+    }
+
+    let forced_only = match profile.auto_select_subtitle {
+        AutoSelectSubtitleMode::AlwaysEnabled => false,
+        // Audio in a language the user understands only gets the forced
+        // subtitles covering the parts in other languages.
+        AutoSelectSubtitleMode::ShownWithForeignAudio => audio.is_none_or(|audio| {
+            !audio.has_language() || languages.iter().any(|language| audio.is_language(language))
+        }),
+        _ => return streams.iter().find(|stream| stream.is_selected()).copied(),
+    };
+
+    let is_forced = |stream: &SubtitleStream| stream.forced == Some(true);
+    let is_sdh = |stream: &SubtitleStream| stream.hearing_impaired == Some(true);
+
+    let acceptable = |stream: &&&SubtitleStream| {
+        let forced_ok = match profile.default_subtitle_forced {
+            DefaultSubtitleForced::OnlyShowForcedSubtitles => is_forced(stream),
+            DefaultSubtitleForced::OnlyShowNonForcedSubtitles => !is_forced(stream),
+            _ => true,
+        } && (!forced_only || is_forced(stream));
+        let sdh_ok = match profile.default_subtitle_accessibility {
+            DefaultSubtitleAccessibility::OnlyShowSdhSubtitles => is_sdh(stream),
+            DefaultSubtitleAccessibility::OnlyShowNonSdhSubtitles => !is_sdh(stream),
+            _ => true,
+        };
+        forced_ok && sdh_ok
+    };
+
+    // Lower ranks are preferred.
+    let rank = |stream: &&SubtitleStream| {
+        let forced_rank = match profile.default_subtitle_forced {
+            DefaultSubtitleForced::PreferForcedSubtitles => !is_forced(stream),
+            _ => is_forced(stream),
+        };
+        let sdh_rank = match profile.default_subtitle_accessibility {
+            DefaultSubtitleAccessibility::PreferSdhSubtitles => !is_sdh(stream),
+            _ => is_sdh(stream),
+        };
+        (forced_rank, sdh_rank)
+    };
+
+    // Without any language preference every stream is a candidate.
+    let languages: Vec<Option<&str>> = if languages.is_empty() {
+        vec![None]
+    } else {
+        languages.into_iter().map(Some).collect()
+    };
+
+    for language in languages {
+        let mut matching: Vec<&SubtitleStream> = streams
+            .iter()
+            .filter(|stream| language.is_none_or(|language| stream.is_language(language)))
+            .filter(acceptable)
+            .copied()
+            .collect();
+        matching.sort_by_key(rank);
+        if let Some(stream) = matching.first() {
+            return Some(stream);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        media_container::{server::library::MetadataMediaContainer, MediaContainerWrapper},
+        myplex::account::MyPlexAccount,
+    };
+
+    fn part() -> Part {
+        let wrapper: MediaContainerWrapper<MetadataMediaContainer> = serde_json::from_str(
+            include_str!("../../../tests/mocks/transcode/metadata_159637.json"),
+        )
+        .expect("failed to parse metadata");
+        let metadata = wrapper.media_container.metadata.into_iter().next().unwrap();
+        metadata.media.unwrap()[0].parts[0].clone()
+    }
+
+    fn profile() -> Profile {
+        let account: MyPlexAccount = serde_json::from_str(include_str!(
+            "../../../tests/mocks/myplex/api/v2/user/user_info_plexpass.json"
+        ))
+        .expect("failed to parse account");
+        account.profile
+    }
+
+    fn subtitle_mut<'p>(part: &'p mut Part, id: &str) -> &'p mut SubtitleStream {
+        part.streams
+            .iter_mut()
+            .flatten()
+            .find_map(|stream| match stream {
+                Stream::Subtitle(stream) if stream.id == id => Some(stream),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    fn set_audio_language(part: &mut Part, tag: &str, code: &str) {
+        for stream in part.streams.iter_mut().flatten() {
+            if let Stream::Audio(stream) = stream {
+                stream.language_tag = Some(tag.to_owned());
+                stream.language_code = Some(code.to_owned());
+            }
+        }
+    }
+
+    fn ids(choice: StreamChoice<'_>) -> (Option<&str>, Option<&str>) {
+        (
+            choice.audio.map(|stream| stream.id.as_str()),
+            choice.subtitle.map(|stream| stream.id.as_str()),
+        )
+    }
+
+    #[plex_api_test_helper::offline_test]
+    fn manually_selected() {
+        let part = part();
+        let profile = profile();
+
+        let choice = StreamChoice::resolve(&part, &profile);
+        assert_eq!(ids(choice), (Some("566076"), None));
+
+        let mut options = VideoTranscodeOptions::default();
+        choice.apply_to(&mut options);
+        assert_eq!(options.audio_stream.as_deref(), Some("566076"));
+        assert_eq!(options.subtitle_stream, Some(SubtitleSelection::None));
+    }
+
+    #[plex_api_test_helper::offline_test]
+    fn always_enabled() {
+        let part = part();
+        let mut profile = profile();
+        profile.auto_select_subtitle = AutoSelectSubtitleMode::AlwaysEnabled;
+
+        let choice = StreamChoice::resolve(&part, &profile);
+        assert_eq!(ids(choice), (Some("566076"), Some("566077")));
+
+        profile.default_subtitle_accessibility = DefaultSubtitleAccessibility::PreferSdhSubtitles;
+        let choice = StreamChoice::resolve(&part, &profile);
+        assert_eq!(ids(choice), (Some("566076"), Some("566078")));
+
+        profile.default_subtitle_language = Some("fr".to_owned());
+        let choice = StreamChoice::resolve(&part, &profile);
+        assert_eq!(ids(choice), (Some("566076"), None));
+    }
+
+    #[plex_api_test_helper::offline_test]
+    fn shown_with_foreign_audio() {
+        let mut part = part();
+        let mut profile = profile();
+        profile.auto_select_subtitle = AutoSelectSubtitleMode::ShownWithForeignAudio;
+
+        // Native audio without forced subtitles.
+        let choice = StreamChoice::resolve(&part, &profile);
+        assert_eq!(ids(choice), (Some("566076"), None));
+
+        // Native audio only shows forced subtitles.
+        subtitle_mut(&mut part, "566078").forced = Some(true);
+        let choice = StreamChoice::resolve(&part, &profile);
+        assert_eq!(ids(choice), (Some("566076"), Some("566078")));
+
+        profile.default_subtitle_forced = DefaultSubtitleForced::OnlyShowNonForcedSubtitles;
+        let choice = StreamChoice::resolve(&part, &profile);
+        assert_eq!(ids(choice), (Some("566076"), None));
+
+        // Foreign audio shows full subtitles.
+        set_audio_language(&mut part, "ja", "jpn");
+        profile.default_subtitle_forced = DefaultSubtitleForced::PreferNonForcedSubtitles;
+        let choice = StreamChoice::resolve(&part, &profile);
+        assert_eq!(ids(choice), (Some("566076"), Some("566077")));
+    }
+
+    #[plex_api_test_helper::offline_test]
+    fn audio_language() {
+        let mut part = part();
+        let mut profile = profile();
+
+        // Adds a German dub alongside the original English audio.
+        let streams = part.streams.as_mut().unwrap();
+        let mut dub = match &streams[1] {
+            Stream::Audio(stream) => stream.clone(),
+            _ => panic!("Unexpected stream type"),
+        };
+        dub.id = "1".to_owned();
+        dub.selected = None;
+        dub.default = None;
+        dub.language_tag = Some("de".to_owned());
+        dub.language_code = Some("ger".to_owned());
+        streams.push(Stream::Audio(dub));
+
+        let choice = StreamChoice::resolve(&part, &profile);
+        assert_eq!(ids(choice), (Some("566076"), None));
+
+        profile.default_audio_languages = Some(vec!["fr".to_owned(), "de-DE".to_owned()]);
+        let choice = StreamChoice::resolve(&part, &profile);
+        assert_eq!(ids(choice), (Some("1"), None));
+
+        // Without auto selection the stream selected on the server is kept.
+        profile.auto_select_audio = false;
+        let choice = StreamChoice::resolve(&part, &profile);
+        assert_eq!(ids(choice), (Some("566076"), None));
+    }
+}
@@ -1,10 +1,30 @@
+pub mod activity;
+pub mod history;
+pub mod hub;
 pub mod library;
+pub mod notification;
+pub mod play_queue;
+pub mod playback;
 pub(crate) mod prefs;
+pub mod session;
 pub mod transcode;
 
 use self::{
-    library::{metadata_items, FromMetadata, Item, Library, MediaItem, MetadataItem},
+    activity::{activities, cancel_activity, start_activity, ActivityHandle},
+    history::{history, HistoryEntry, HistoryOptions},
+    hub::{hubs, Hub},
+    library::{
+        create_collection, create_playlist, item_by_id, library_sections, metadata_items,
+        playlist_item_id, Collection, CollectionContent, CollectionLibrary, FromMetadata, Item,
+        ItemStream, Library, LibrarySearch, MatchFeature, MediaItem, MetadataItem, NewLibrary,
+        PagingOptions, Playlist, PlaylistContent, PlaylistItem,
+    },
+    notification::NotificationStream,
+    play_queue::{
+        create_play_queue, create_search_play_queue, play_queue, PlayQueue, PlayQueueOptions,
+    },
     prefs::Preferences,
+    session::Session,
     transcode::{
         transcode_artwork, transcode_session_stats, ArtTranscodeOptions, TranscodeSession,
         TranscodeSessionsMediaContainer,
@@ -13,17 +33,29 @@ use self::{
 #[cfg(not(feature = "tests_deny_unknown_fields"))]
 use crate::media_container::server::library::LibraryType;
 use crate::{
-    http_client::HttpClient,
+    http_client::{Failover, HttpClient},
     media_container::{
-        server::{library::ContentDirectory, MediaProviderFeature, Server as ServerMediaContainer},
+        server::{
+            activity::Activity,
+            butler::{ButlerTask, ButlerTasksWrapper},
+            library::{
+                Agent, AgentsMediaContainer, ContentDirectory, LibrarySection, Scanner,
+                ScannersMediaContainer, SearchType,
+            },
+            MediaProviderFeature, Server as ServerMediaContainer,
+        },
         MediaContainerWrapper,
     },
     myplex::MyPlex,
     url::{
-        SERVER_MEDIA_PROVIDERS, SERVER_MYPLEX_ACCOUNT, SERVER_MYPLEX_CLAIM, SERVER_SCROBBLE,
-        SERVER_TIMELINE, SERVER_TRANSCODE_SESSIONS, SERVER_UNSCROBBLE,
+        SERVER_AGENTS, SERVER_BUTLER, SERVER_CLEAN_BUNDLES, SERVER_COLLECTIONS,
+        SERVER_CONTINUE_WATCHING, SERVER_HUBS, SERVER_HUBS_SEARCH, SERVER_LIBRARIES_REFRESH,
+        SERVER_LIBRARY_SECTIONS, SERVER_MEDIA_PROVIDERS, SERVER_MYPLEX_ACCOUNT,
+        SERVER_MYPLEX_CLAIM, SERVER_ON_DECK, SERVER_OPTIMIZE_DATABASE, SERVER_PLAYLISTS,
+        SERVER_RECENTLY_ADDED, SERVER_SCANNERS, SERVER_SCROBBLE, SERVER_SESSIONS, SERVER_TIMELINE,
+        SERVER_TRANSCODE_SESSIONS, SERVER_UNSCROBBLE,
     },
-    Error, HttpClientBuilder, Result,
+    HttpClientBuilder, Result,
 };
 use futures::AsyncWrite;
 use http::{StatusCode, Uri};
@@ -31,8 +63,14 @@ use isahc::AsyncReadResponseExt;
 use std::{
     collections::HashMap,
     fmt::{self, Debug},
+    time::Duration,
 };
 
+/// Builds the uri the server uses to refer to its own library contents.
+pub(crate) fn library_uri(machine_identifier: &str, path: &str) -> String {
+    format!("server://{machine_identifier}/com.plexapp.plugins.library{path}")
+}
+
 struct Query {
     params: HashMap<String, String>,
 }
@@ -72,6 +110,7 @@ pub struct Server {
     client: HttpClient,
     pub myplex_api_url: Uri,
     pub media_container: ServerMediaContainer,
+    latency: Option<Duration>,
 }
 
 impl Server {
@@ -83,6 +122,7 @@ impl Server {
             media_container: media_container_wrapper.media_container,
             client,
             myplex_api_url,
+            latency: None,
         })
     }
 
@@ -101,25 +141,29 @@ impl Server {
         .await
     }
 
-    fn content(&self) -> Option<&Vec<ContentDirectory>> {
-        if let Some(provider) = self
-            .media_container
+    /// The features advertised by the library provider.
+    fn library_features(&self) -> &[MediaProviderFeature] {
+        self.media_container
             .media_providers
             .iter()
             .find(|p| p.identifier == "com.plexapp.plugins.library")
-        {
-            for feature in &provider.features {
-                if let MediaProviderFeature::Content {
-                    key: _,
-                    ref directory,
-                } = feature
-                {
-                    return Some(directory);
-                }
-            }
-        }
+            .map(|provider| provider.features.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// The server's support for fixing the match of library items, see
+    /// [`MetadataItemWithMatching`](crate::library::MetadataItemWithMatching).
+    pub fn match_feature(&self) -> Result<MatchFeature> {
+        MatchFeature::from_features(self.library_features())
+    }
 
-        None
+    fn content(&self) -> Option<&Vec<ContentDirectory>> {
+        self.library_features()
+            .iter()
+            .find_map(|feature| match feature {
+                MediaProviderFeature::Content { directory, .. } => Some(directory),
+                _ => None,
+            })
     }
 
     pub fn libraries(&self) -> Vec<Library> {
@@ -140,6 +184,73 @@ impl Server {
         }
     }
 
+    /// Retrieves all of the library sections including their folders.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn library_sections(&self) -> Result<Vec<LibrarySection>> {
+        library_sections(&self.client).await
+    }
+
+    /// Creates a new library section. Use [`Server::refresh`] to retrieve
+    /// it afterwards.
+    ///
+    /// The agent and scanner are checked against the ones the server offers
+    /// for the type of library before anything is created.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn create_library(&self, library: &NewLibrary) -> Result {
+        if library.locations.is_empty() {
+            return Err(crate::Error::NoItemsProvided);
+        }
+
+        if let Some(media_type) = library.media_type() {
+            let agents = self.agents(Some(media_type)).await?;
+            if !agents.iter().any(|agent| agent.identifier == library.agent) {
+                return Err(crate::Error::LibraryAgentNotSupported(
+                    library.agent.clone(),
+                ));
+            }
+
+            let scanners = self.scanners(media_type).await?;
+            if !scanners
+                .iter()
+                .any(|scanner| scanner.name == library.scanner)
+            {
+                return Err(crate::Error::LibraryScannerNotSupported(
+                    library.scanner.clone(),
+                ));
+            }
+        }
+
+        self.client
+            .post(format!("{SERVER_LIBRARY_SECTIONS}?{}", library.query()?))
+            .consume()
+            .await
+    }
+
+    /// Retrieves the metadata agents available on the server. `media_type`
+    /// limits the list to the agents supporting that type of items.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn agents(&self, media_type: Option<SearchType>) -> Result<Vec<Agent>> {
+        let path = match media_type {
+            Some(media_type) => format!("{SERVER_AGENTS}?mediaType={media_type}"),
+            None => SERVER_AGENTS.to_owned(),
+        };
+
+        let wrapper: MediaContainerWrapper<AgentsMediaContainer> =
+            self.client.get(path).json().await?;
+        Ok(wrapper.media_container.agents)
+    }
+
+    /// Retrieves the scanners available on the server for the type of items.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn scanners(&self, media_type: SearchType) -> Result<Vec<Scanner>> {
+        let wrapper: MediaContainerWrapper<ScannersMediaContainer> = self
+            .client
+            .get(format!("{SERVER_SCANNERS}/{media_type}"))
+            .json()
+            .await?;
+        Ok(wrapper.media_container.scanners)
+    }
+
     /// Given the path to some item's artwork (`art` or `thumb` properties for
     /// example but many other types of images will work) this will request a
     /// scaled version of that image be written to the passed writer as a JPEG.
@@ -177,6 +288,100 @@ impl Server {
             .collect())
     }
 
+    /// Retrieves the items currently being played by the users of this server.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn sessions(&self) -> Result<Vec<Session>> {
+        Ok(metadata_items(&self.client, SERVER_SESSIONS)
+            .await?
+            .into_iter()
+            .map(|item| Session::new(self.client.clone(), item))
+            .collect())
+    }
+
+    /// Streams the watch history of the server, most recent first.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn history(
+        &self,
+        options: HistoryOptions,
+        paging: PagingOptions,
+    ) -> Result<ItemStream<HistoryEntry>> {
+        history(&self.client, &options, paging).await
+    }
+
+    /// Subscribes to the events happening on the server, e.g. playback state
+    /// changes, library updates or activity progress. The connection is only
+    /// established once the stream is polled.
+    ///
+    /// The events are received using server sent events, the websocket
+    /// endpoint is not supported.
+    pub fn notifications(&self) -> NotificationStream {
+        NotificationStream::new(self.client.clone())
+    }
+
+    /// Retrieves the long-running tasks currently performed by the server,
+    /// e.g. library scans or media analysis.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn activities(&self) -> Result<Vec<Activity>> {
+        activities(&self.client).await
+    }
+
+    /// Cancels the activity with the passed uuid.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn cancel_activity(&self, uuid: &str) -> Result {
+        cancel_activity(&self.client, uuid).await
+    }
+
+    /// Retrieves the maintenance tasks the server runs on a schedule.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn butler_tasks(&self) -> Result<Vec<ButlerTask>> {
+        let wrapper: ButlerTasksWrapper = self.client.get(SERVER_BUTLER).json().await?;
+        Ok(wrapper.butler_tasks.tasks)
+    }
+
+    /// Starts the scheduled task with the passed name, e.g. `BackupDatabase`,
+    /// immediately.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn start_butler_task(&self, name: &str) -> Result {
+        self.client
+            .post(format!("{SERVER_BUTLER}/{name}"))
+            .consume()
+            .await
+    }
+
+    /// Stops the scheduled task with the passed name if it's running.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn stop_butler_task(&self, name: &str) -> Result {
+        self.client
+            .delete(format!("{SERVER_BUTLER}/{name}"))
+            .consume()
+            .await
+    }
+
+    /// Scans all of the libraries for new, changed and removed files.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn scan_libraries(&self) -> Result<ActivityHandle> {
+        start_activity(
+            &self.client,
+            self.client.get(SERVER_LIBRARIES_REFRESH),
+            None,
+        )
+        .await
+    }
+
+    /// Removes the metadata bundles no longer used by any item.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn clean_bundles(&self) -> Result<ActivityHandle> {
+        let url = format!("{SERVER_CLEAN_BUNDLES}?async=1");
+        start_activity(&self.client, self.client.put(url), None).await
+    }
+
+    /// Optimizes the server's database.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn optimize_database(&self) -> Result<ActivityHandle> {
+        let url = format!("{SERVER_OPTIMIZE_DATABASE}?async=1");
+        start_activity(&self.client, self.client.put(url), None).await
+    }
+
     /// Retrieves the transcode session with the passed ID.
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn transcode_session(&self, session_id: &str) -> Result<TranscodeSession> {
@@ -188,26 +393,248 @@ impl Server {
     /// their rating key.
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn item_by_id(&self, rating_key: &str) -> Result<Item> {
-        let path = format!("/library/metadata/{rating_key}?includeConcerts=1&includeExtras=1&includePopularLeaves=1&includePreferences=1&includeReviews=1&includeOnDeck=1&includeChapters=1&includeStations=1&includeExternalMedia=1&asyncAugmentMetadata=1&asyncCheckFiles=1&asyncRefreshAnalysis=1&asyncRefreshLocalMediaAgent=1&includeMarkers=1");
-
-        match metadata_items(&self.client, &path).await {
-            Ok(items) => items.into_iter().next().ok_or(Error::ItemNotFound),
-            Err(Error::UnexpectedApiResponse {
-                status_code,
-                content,
-            }) => {
-                // A 404 error indicates the item does not exist.
-                if status_code == 404 {
-                    Err(Error::ItemNotFound)
-                } else {
-                    Err(Error::UnexpectedApiResponse {
-                        status_code,
-                        content,
-                    })
-                }
-            }
-            Err(err) => Err(err),
+        item_by_id(&self.client, rating_key).await
+    }
+
+    /// Retrieves the hubs shown on the home screen.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn hubs(&self) -> Result<Vec<Hub>> {
+        hubs(&self.client, SERVER_HUBS).await
+    }
+
+    /// Retrieves the items the user has started but not finished watching,
+    /// across all libraries.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn continue_watching(&self) -> Result<Vec<Item>> {
+        metadata_items(&self.client, SERVER_CONTINUE_WATCHING).await
+    }
+
+    /// Retrieves the items that are On Deck across all libraries.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn on_deck(&self) -> Result<Vec<Item>> {
+        metadata_items(&self.client, SERVER_ON_DECK).await
+    }
+
+    /// Retrieves the items recently added to any of the libraries.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn recently_added(&self) -> Result<Vec<Item>> {
+        metadata_items(&self.client, SERVER_RECENTLY_ADDED).await
+    }
+
+    /// Searches all of the libraries, returning the results grouped into
+    /// hubs by type. `limit` restricts the number of items in each hub.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn search_hubs(&self, query: &str, limit: Option<u32>) -> Result<Vec<Hub>> {
+        let mut search = Query::new().param("query", query);
+        if let Some(limit) = limit {
+            search = search.param("limit", limit.to_string());
+        }
+
+        hubs(&self.client, &format!("{SERVER_HUBS_SEARCH}?{search}")).await
+    }
+
+    fn library_uri(&self, path: &str) -> String {
+        library_uri(self.machine_identifier(), path)
+    }
+
+    fn items_uri<I: MetadataItem>(&self, items: &[I]) -> Result<String> {
+        if items.is_empty() {
+            return Err(crate::Error::NoItemsProvided);
+        }
+
+        let keys = items
+            .iter()
+            .map(|item| item.rating_key())
+            .collect::<Vec<_>>()
+            .join(",");
+        Ok(self.library_uri(&format!("/library/metadata/{keys}")))
+    }
+
+    /// Creates a playlist made of the items, in the order given.
+    #[tracing::instrument(level = "debug", skip(self, items))]
+    pub async fn create_playlist<M, I>(&self, title: &str, items: &[I]) -> Result<Playlist<M>>
+    where
+        M: PlaylistContent,
+        I: PlaylistItem<M>,
+    {
+        create_playlist(&self.client, title, false, &self.items_uri(items)?).await
+    }
+
+    /// Creates a smart playlist, which is made of the items matching the
+    /// library search at any given time.
+    #[tracing::instrument(level = "debug", skip(self, search))]
+    pub async fn create_smart_playlist<M, I>(
+        &self,
+        title: &str,
+        search: &LibrarySearch<I>,
+    ) -> Result<Playlist<M>>
+    where
+        M: PlaylistContent,
+        I: PlaylistItem<M>,
+    {
+        let uri = self.library_uri(&search.path().await?);
+        create_playlist(&self.client, title, true, &uri).await
+    }
+
+    /// Adds the items to the end of the playlist.
+    #[tracing::instrument(level = "debug", skip_all, fields(playlist.id = playlist.rating_key()))]
+    pub async fn add_to_playlist<M, I>(&self, playlist: &Playlist<M>, items: &[I]) -> Result
+    where
+        M: PlaylistContent,
+        I: PlaylistItem<M>,
+    {
+        let query = serde_urlencoded::to_string([("uri", self.items_uri(items)?)])?;
+        self.client
+            .put(format!(
+                "{SERVER_PLAYLISTS}/{}/items?{query}",
+                playlist.rating_key()
+            ))
+            .consume()
+            .await
+    }
+
+    /// Removes the item from the playlist. The item must have been retrieved
+    /// from the playlist.
+    #[tracing::instrument(level = "debug", skip_all, fields(playlist.id = playlist.rating_key()))]
+    pub async fn remove_from_playlist<M>(&self, playlist: &Playlist<M>, item: &M) -> Result
+    where
+        M: PlaylistContent + MetadataItem,
+    {
+        let item_id = playlist_item_id(item)?;
+        self.client
+            .delete(format!(
+                "{SERVER_PLAYLISTS}/{}/items/{item_id}",
+                playlist.rating_key()
+            ))
+            .consume()
+            .await
+    }
+
+    /// Moves the item so that it follows `after`, or to the beginning of the
+    /// playlist if `after` is `None`. Both items must have been retrieved
+    /// from the playlist.
+    #[tracing::instrument(level = "debug", skip_all, fields(playlist.id = playlist.rating_key()))]
+    pub async fn move_in_playlist<M>(
+        &self,
+        playlist: &Playlist<M>,
+        item: &M,
+        after: Option<&M>,
+    ) -> Result
+    where
+        M: PlaylistContent + MetadataItem,
+    {
+        let mut path = format!(
+            "{SERVER_PLAYLISTS}/{}/items/{}/move",
+            playlist.rating_key(),
+            playlist_item_id(item)?
+        );
+        if let Some(after) = after {
+            path = format!("{path}?after={}", playlist_item_id(after)?);
         }
+
+        self.client.put(path).consume().await
+    }
+
+    /// Creates a collection in the library made of the items.
+    #[tracing::instrument(level = "debug", skip(self, library, items))]
+    pub async fn create_collection<L>(
+        &self,
+        library: &L,
+        title: &str,
+        items: &[L::Item],
+    ) -> Result<Collection<L::Item>>
+    where
+        L: CollectionLibrary,
+    {
+        create_collection(
+            &self.client,
+            library.id(),
+            title,
+            false,
+            &self.items_uri(items)?,
+        )
+        .await
+    }
+
+    /// Creates a smart collection, which is made of the items matching the
+    /// library search at any given time.
+    #[tracing::instrument(level = "debug", skip(self, search))]
+    pub async fn create_smart_collection<M>(
+        &self,
+        title: &str,
+        search: &LibrarySearch<M>,
+    ) -> Result<Collection<M>>
+    where
+        M: CollectionContent,
+    {
+        let uri = self.library_uri(&search.path().await?);
+        create_collection(&self.client, search.section_id(), title, true, &uri).await
+    }
+
+    /// Adds the items to the collection.
+    #[tracing::instrument(level = "debug", skip_all, fields(collection.id = collection.rating_key()))]
+    pub async fn add_to_collection<M>(&self, collection: &Collection<M>, items: &[M]) -> Result
+    where
+        M: CollectionContent,
+    {
+        let query = serde_urlencoded::to_string([("uri", self.items_uri(items)?)])?;
+        self.client
+            .put(format!(
+                "{SERVER_COLLECTIONS}/{}/items?{query}",
+                collection.rating_key()
+            ))
+            .consume()
+            .await
+    }
+
+    /// Removes the item from the collection.
+    #[tracing::instrument(level = "debug", skip_all, fields(collection.id = collection.rating_key()))]
+    pub async fn remove_from_collection<M>(&self, collection: &Collection<M>, item: &M) -> Result
+    where
+        M: CollectionContent,
+    {
+        self.client
+            .delete(format!(
+                "{SERVER_COLLECTIONS}/{}/items/{}",
+                collection.rating_key(),
+                item.rating_key()
+            ))
+            .consume()
+            .await
+    }
+
+    /// Creates a play queue starting with the item, e.g. a movie, an album or
+    /// a playlist.
+    #[tracing::instrument(level = "debug", skip(self, item), fields(item.id = item.rating_key()))]
+    pub async fn create_play_queue<I: MetadataItem>(
+        &self,
+        item: &I,
+        options: PlayQueueOptions,
+    ) -> Result<PlayQueue> {
+        create_play_queue(&self.client, self.machine_identifier(), item, options).await
+    }
+
+    /// Creates a play queue made of the items matching the library search.
+    #[tracing::instrument(level = "debug", skip(self, search))]
+    pub async fn create_play_queue_from_search<M>(
+        &self,
+        search: &LibrarySearch<M>,
+        options: PlayQueueOptions,
+    ) -> Result<PlayQueue> {
+        create_search_play_queue(
+            &self.client,
+            self.machine_identifier(),
+            search.search_type(),
+            &search.path().await?,
+            options,
+        )
+        .await
+    }
+
+    /// Retrieves an existing play queue, e.g. one created by another client.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn play_queue(&self, id: u64) -> Result<PlayQueue> {
+        play_queue(&self.client, self.machine_identifier(), id).await
     }
 
     /// Marks a media item as fully watched increasing its view count by one.
@@ -242,6 +669,9 @@ impl Server {
 
     /// Sets a media item's playback position in milliseconds. The server currently ignores any
     /// positions equal to or less than 60000ms. The time sets the time the item was last viewed.
+    ///
+    /// Players should use a [`PlaybackReporter`](playback::PlaybackReporter) to report ongoing
+    /// playback instead.
     pub async fn update_timeline<M: MediaItem + FromMetadata>(
         &self,
         item: &M,
@@ -269,7 +699,10 @@ impl Server {
 
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn refresh(self) -> Result<Self> {
-        Self::build(self.client, self.myplex_api_url).await
+        let mut server = Self::build(self.client, self.myplex_api_url).await?;
+        server.client.follow_failover();
+        server.latency = self.latency;
+        Ok(server)
     }
 
     pub fn myplex(&self) -> Result<MyPlex> {
@@ -321,6 +754,20 @@ impl Server {
         &self.client
     }
 
+    pub(crate) fn set_failover(&mut self, failover: Failover) {
+        self.client.set_failover(failover);
+    }
+
+    /// How long the server took to answer when it was connected to with
+    /// [`Device::connect`](crate::device::Device::connect).
+    pub fn latency(&self) -> Option<Duration> {
+        self.latency
+    }
+
+    pub(crate) fn set_latency(&mut self, latency: Duration) {
+        self.latency = Some(latency);
+    }
+
     pub async fn preferences<'a>(&self) -> Result<Preferences<'a>> {
         Preferences::new(&self.client).await
     }
@@ -0,0 +1,160 @@
+use std::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{
+    io::{BufReader, Lines},
+    stream::{self, BoxStream},
+    AsyncBufReadExt, Stream, StreamExt,
+};
+use futures_timer::Delay;
+use http::StatusCode;
+use isahc::AsyncBody;
+
+use crate::{
+    media_container::server::notification::Notification, url::SERVER_NOTIFICATIONS, Error,
+    HttpClient, Result,
+};
+
+const RECONNECT_DELAY_MIN: Duration = Duration::from_secs(1);
+const RECONNECT_DELAY_MAX: Duration = Duration::from_secs(60);
+
+type EventLines = Lines<BufReader<AsyncBody>>;
+
+enum State {
+    Disconnected { delay: Duration },
+    Connected(EventLines),
+}
+
+async fn connect(client: &HttpClient) -> Result<EventLines> {
+    let response = client
+        .get(SERVER_NOTIFICATIONS)
+        .timeout(None)
+        .header("Accept", "text/event-stream")
+        .send()
+        .await?;
+
+    if response.status() != StatusCode::OK {
+        return Err(Error::from_response(response).await);
+    }
+
+    Ok(BufReader::new(response.into_body()).lines())
+}
+
+/// Reads the next server sent event, returning its name and data. Returns
+/// `None` once the server closes the connection.
+async fn next_event(lines: &mut EventLines) -> Result<Option<(String, String)>> {
+    let mut event = String::new();
+    let mut data: Vec<String> = Vec::new();
+
+    while let Some(line) = lines.next().await {
+        let line = line?;
+
+        if line.is_empty() {
+            if !data.is_empty() {
+                if event.is_empty() {
+                    event.push_str("message");
+                }
+                return Ok(Some((event, data.join("\n"))));
+            }
+            event.clear();
+            continue;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            // Lines starting with a colon are comments.
+            Some(("", _)) => continue,
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line.as_str(), ""),
+        };
+
+        match field {
+            "event" => value.clone_into(&mut event),
+            "data" => data.push(value.to_owned()),
+            _ => {}
+        }
+    }
+
+    Ok(None)
+}
+
+/// A never ending stream of the events happening on the server.
+///
+/// The stream reconnects automatically whenever the connection is lost,
+/// yielding the connection errors along the way. Drop the stream to
+/// disconnect.
+pub struct NotificationStream {
+    inner: BoxStream<'static, Result<Notification>>,
+}
+
+impl NotificationStream {
+    pub(crate) fn new(client: HttpClient) -> Self {
+        let initial = State::Disconnected {
+            delay: Duration::ZERO,
+        };
+
+        let inner = stream::unfold((client, initial), |(client, mut state)| async move {
+            loop {
+                state = match state {
+                    State::Disconnected { delay } => {
+                        if !delay.is_zero() {
+                            Delay::new(delay).await;
+                        }
+
+                        match connect(&client).await {
+                            Ok(lines) => State::Connected(lines),
+                            Err(err) => {
+                                let delay =
+                                    (delay * 2).clamp(RECONNECT_DELAY_MIN, RECONNECT_DELAY_MAX);
+                                return Some((Err(err), (client, State::Disconnected { delay })));
+                            }
+                        }
+                    }
+                    State::Connected(mut lines) => match next_event(&mut lines).await {
+                        Ok(Some((event, data))) => match Notification::from_event(&event, &data) {
+                            Ok(Some(notification)) => {
+                                return Some((Ok(notification), (client, State::Connected(lines))))
+                            }
+                            Ok(None) => State::Connected(lines),
+                            Err(err) => {
+                                return Some((Err(err.into()), (client, State::Connected(lines))))
+                            }
+                        },
+                        Ok(None) => {
+                            tracing::debug!("Notifications connection closed, reconnecting");
+                            State::Disconnected {
+                                delay: RECONNECT_DELAY_MIN,
+                            }
+                        }
+                        Err(err) => {
+                            let state = State::Disconnected {
+                                delay: RECONNECT_DELAY_MIN,
+                            };
+                            return Some((Err(err), (client, state)));
+                        }
+                    },
+                }
+            }
+        })
+        .boxed();
+
+        Self { inner }
+    }
+}
+
+impl fmt::Debug for NotificationStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NotificationStream").finish_non_exhaustive()
+    }
+}
+
+impl Stream for NotificationStream {
+    type Item = Result<Notification>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
@@ -0,0 +1,351 @@
+use crate::{
+    media_container::{
+        server::{
+            library::{Metadata, MetadataType, PlaylistMetadataType, SearchType},
+            play_queue::PlayQueueMediaContainer,
+        },
+        MediaContainerWrapper,
+    },
+    url::{SERVER_COLLECTIONS, SERVER_PLAY_QUEUES},
+    Error, HttpClient, Result,
+};
+
+use super::{
+    library::{FromMetadata, Item, MetadataItem},
+    library_uri,
+};
+
+/// Controls the playback order of a new play queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlayQueueOptions {
+    pub shuffle: bool,
+    /// Start over once the last item was played.
+    pub repeat: bool,
+    /// Keep playing the items that follow the item the queue was created
+    /// from, e.g. the next episodes of a show.
+    pub continuous: bool,
+}
+
+/// Where new items are added to a play queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueuePosition {
+    /// Right after the item currently playing.
+    Next,
+    /// At the end of the queue.
+    Last,
+}
+
+fn flag(value: bool) -> String {
+    if value { "1" } else { "0" }.to_owned()
+}
+
+/// Returns the type of play queue and the parameter identifying the item.
+fn item_source(
+    machine_identifier: &str,
+    metadata: &Metadata,
+) -> Result<(&'static str, (&'static str, String))> {
+    // The key of shows, albums and the like lists their children, the
+    // server has to be given the item itself to expand it into its leaves.
+    let uri = || {
+        let path = match metadata.metadata_type {
+            Some(MetadataType::Collection(_)) => {
+                format!("{SERVER_COLLECTIONS}/{}", metadata.rating_key)
+            }
+            _ => format!("/library/metadata/{}", metadata.rating_key),
+        };
+        ("uri", library_uri(machine_identifier, &path))
+    };
+
+    match metadata.metadata_type {
+        Some(MetadataType::Playlist(playlist_type)) => {
+            let queue_type = match playlist_type {
+                PlaylistMetadataType::Video => "video",
+                PlaylistMetadataType::Audio => "audio",
+                PlaylistMetadataType::Photo => "photo",
+                #[cfg(not(feature = "tests_deny_unknown_fields"))]
+                PlaylistMetadataType::Unknown => return Err(Error::PlayQueueItemNotSupported),
+            };
+            Ok((queue_type, ("playlistID", metadata.rating_key.clone())))
+        }
+        Some(
+            MetadataType::Movie
+            | MetadataType::Episode
+            | MetadataType::Show
+            | MetadataType::Season
+            | MetadataType::Clip(_)
+            | MetadataType::Collection(_),
+        ) => Ok(("video", uri())),
+        Some(MetadataType::Artist | MetadataType::MusicAlbum | MetadataType::Track) => {
+            Ok(("audio", uri()))
+        }
+        Some(MetadataType::Photo) => Ok(("photo", uri())),
+        _ => Err(Error::PlayQueueItemNotSupported),
+    }
+}
+
+fn search_queue_type(search_type: SearchType) -> Result<&'static str> {
+    match search_type {
+        SearchType::Movie
+        | SearchType::Show
+        | SearchType::Season
+        | SearchType::Episode
+        | SearchType::Clip => Ok("video"),
+        SearchType::Artist | SearchType::Album | SearchType::Track => Ok("audio"),
+        SearchType::Photo | SearchType::Picture | SearchType::PhotoAlbum => Ok("photo"),
+        _ => Err(Error::PlayQueueItemNotSupported),
+    }
+}
+
+/// Creates a play queue made of the item, e.g. a single movie, the tracks of
+/// an album or the items of a playlist.
+#[tracing::instrument(level = "trace", skip(client, item))]
+pub(crate) async fn create_play_queue<I: MetadataItem>(
+    client: &HttpClient,
+    machine_identifier: &str,
+    item: &I,
+    options: PlayQueueOptions,
+) -> Result<PlayQueue> {
+    let (queue_type, source) = item_source(machine_identifier, item.metadata())?;
+    create(client, machine_identifier, queue_type, source, options).await
+}
+
+/// Creates a play queue made of the items found by a library search.
+#[tracing::instrument(level = "trace", skip(client))]
+pub(crate) async fn create_search_play_queue(
+    client: &HttpClient,
+    machine_identifier: &str,
+    search_type: SearchType,
+    path: &str,
+    options: PlayQueueOptions,
+) -> Result<PlayQueue> {
+    let queue_type = search_queue_type(search_type)?;
+    let source = ("uri", library_uri(machine_identifier, path));
+    create(client, machine_identifier, queue_type, source, options).await
+}
+
+async fn create(
+    client: &HttpClient,
+    machine_identifier: &str,
+    queue_type: &str,
+    source: (&str, String),
+    options: PlayQueueOptions,
+) -> Result<PlayQueue> {
+    let query = serde_urlencoded::to_string([
+        ("type", queue_type.to_owned()),
+        source,
+        ("shuffle", flag(options.shuffle)),
+        ("repeat", flag(options.repeat)),
+        ("continuous", flag(options.continuous)),
+        ("own", flag(true)),
+    ])?;
+
+    let wrapper: MediaContainerWrapper<PlayQueueMediaContainer> = client
+        .post(format!("{SERVER_PLAY_QUEUES}?{query}"))
+        .json()
+        .await?;
+
+    Ok(PlayQueue {
+        client: client.clone(),
+        machine_identifier: machine_identifier.to_owned(),
+        container: wrapper.media_container,
+    })
+}
+
+/// Retrieves an existing play queue.
+#[tracing::instrument(level = "trace", skip(client))]
+pub(crate) async fn play_queue(
+    client: &HttpClient,
+    machine_identifier: &str,
+    id: u64,
+) -> Result<PlayQueue> {
+    let wrapper: MediaContainerWrapper<PlayQueueMediaContainer> = client
+        .get(format!("{SERVER_PLAY_QUEUES}/{id}?own=1"))
+        .json()
+        .await?;
+
+    Ok(PlayQueue {
+        client: client.clone(),
+        machine_identifier: machine_identifier.to_owned(),
+        container: wrapper.media_container,
+    })
+}
+
+fn play_queue_item_id(item: &Item) -> Result<u64> {
+    item.metadata()
+        .play_queue_item_id
+        .ok_or(Error::PlayQueueItemIdNotAvailable)
+}
+
+/// A list of items to play in order, kept by the server so that it can be
+/// shared between clients.
+///
+/// The server only returns a window of the items around the selected one.
+/// Every change made to the queue updates the window.
+#[derive(Debug, Clone)]
+pub struct PlayQueue {
+    client: HttpClient,
+    machine_identifier: String,
+    container: PlayQueueMediaContainer,
+}
+
+impl PlayQueue {
+    /// Returns the unique ID of this play queue.
+    pub fn id(&self) -> u64 {
+        self.container.play_queue_id
+    }
+
+    /// Returns the version of this play queue, which changes every time the
+    /// queue does.
+    pub fn version(&self) -> u32 {
+        self.container.play_queue_version
+    }
+
+    pub fn is_shuffled(&self) -> bool {
+        self.container.play_queue_shuffled
+    }
+
+    /// Returns the total number of items in the queue, not just the ones in
+    /// the current window.
+    pub fn total_count(&self) -> u32 {
+        self.container.play_queue_total_count
+    }
+
+    /// Returns the position of the selected item within the whole queue.
+    pub fn selected_offset(&self) -> Option<u32> {
+        self.container.play_queue_selected_item_offset
+    }
+
+    pub fn metadata(&self) -> &PlayQueueMediaContainer {
+        &self.container
+    }
+
+    /// Returns the items in the current window of the queue.
+    pub fn items(&self) -> Vec<Item> {
+        self.container
+            .metadata
+            .iter()
+            .map(|metadata| Item::from_metadata(self.client.clone(), metadata.clone()))
+            .collect()
+    }
+
+    /// Returns the selected item, i.e. the one currently playing.
+    pub fn selected_item(&self) -> Option<Item> {
+        let selected = self.container.play_queue_selected_item_id?;
+        self.container
+            .metadata
+            .iter()
+            .find(|metadata| metadata.play_queue_item_id == Some(selected))
+            .map(|metadata| Item::from_metadata(self.client.clone(), metadata.clone()))
+    }
+
+    fn path(&self) -> String {
+        format!("{SERVER_PLAY_QUEUES}/{}", self.id())
+    }
+
+    fn update(&mut self, wrapper: MediaContainerWrapper<PlayQueueMediaContainer>) {
+        self.container = wrapper.media_container;
+    }
+
+    /// Retrieves the current state of the queue. The window includes up to
+    /// `window` items around `center`, or around the selected item if no
+    /// center is given.
+    #[tracing::instrument(level = "debug", skip(self, center), fields(play_queue.id = self.id()))]
+    pub async fn refresh(&mut self, center: Option<&Item>, window: Option<u32>) -> Result {
+        let mut params = vec![("own", "1".to_owned())];
+        if let Some(center) = center {
+            params.push(("center", play_queue_item_id(center)?.to_string()));
+        }
+        if let Some(window) = window {
+            params.push(("window", window.to_string()));
+        }
+
+        let wrapper = self
+            .client
+            .get(format!(
+                "{}?{}",
+                self.path(),
+                serde_urlencoded::to_string(params)?
+            ))
+            .json()
+            .await?;
+        self.update(wrapper);
+        Ok(())
+    }
+
+    /// Adds the item to the queue, e.g. a track, an album or a playlist.
+    #[tracing::instrument(level = "debug", skip(self, item), fields(play_queue.id = self.id()))]
+    pub async fn add<I: MetadataItem>(&mut self, item: &I, position: QueuePosition) -> Result {
+        let (_, source) = item_source(&self.machine_identifier, item.metadata())?;
+        let query =
+            serde_urlencoded::to_string([source, ("next", flag(position == QueuePosition::Next))])?;
+
+        let wrapper = self
+            .client
+            .put(format!("{}?{query}", self.path()))
+            .json()
+            .await?;
+        self.update(wrapper);
+        Ok(())
+    }
+
+    /// Moves the item so that it follows `after`, or to the beginning of the
+    /// queue if `after` is `None`. Both items must have been retrieved from
+    /// this queue.
+    #[tracing::instrument(level = "debug", skip(self, item, after), fields(play_queue.id = self.id()))]
+    pub async fn move_item(&mut self, item: &Item, after: Option<&Item>) -> Result {
+        let mut path = format!("{}/items/{}/move", self.path(), play_queue_item_id(item)?);
+        if let Some(after) = after {
+            path = format!("{path}?after={}", play_queue_item_id(after)?);
+        }
+
+        let wrapper = self.client.put(path).json().await?;
+        self.update(wrapper);
+        Ok(())
+    }
+
+    /// Removes the item from the queue. The item must have been retrieved
+    /// from this queue.
+    #[tracing::instrument(level = "debug", skip(self, item), fields(play_queue.id = self.id()))]
+    pub async fn remove_item(&mut self, item: &Item) -> Result {
+        let path = format!("{}/items/{}", self.path(), play_queue_item_id(item)?);
+        let wrapper = self.client.delete(path).json().await?;
+        self.update(wrapper);
+        Ok(())
+    }
+
+    /// Removes all of the items from the queue.
+    #[tracing::instrument(level = "debug", skip(self), fields(play_queue.id = self.id()))]
+    pub async fn clear(&mut self) -> Result {
+        let wrapper = self
+            .client
+            .delete(format!("{}/items", self.path()))
+            .json()
+            .await?;
+        self.update(wrapper);
+        Ok(())
+    }
+
+    /// Shuffles the items following the selected one.
+    #[tracing::instrument(level = "debug", skip(self), fields(play_queue.id = self.id()))]
+    pub async fn shuffle(&mut self) -> Result {
+        let wrapper = self
+            .client
+            .put(format!("{}/shuffle", self.path()))
+            .json()
+            .await?;
+        self.update(wrapper);
+        Ok(())
+    }
+
+    /// Restores the original order of the items.
+    #[tracing::instrument(level = "debug", skip(self), fields(play_queue.id = self.id()))]
+    pub async fn unshuffle(&mut self) -> Result {
+        let wrapper = self
+            .client
+            .put(format!("{}/unshuffle", self.path()))
+            .json()
+            .await?;
+        self.update(wrapper);
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+use std::time::{Duration, Instant};
+
+pub use crate::media_container::player::PlaybackState;
+use crate::{
+    url::{SERVER_SCROBBLE, SERVER_TIMELINE},
+    Result,
+};
+
+use super::{
+    library::MediaItem,
+    transcode::{session_id, TranscodeSession},
+};
+
+/// How often the playback position is reported while the state stays the
+/// same. Plex clients report every 10 seconds.
+const DEFAULT_REPORT_INTERVAL: Duration = Duration::from_secs(10);
+/// The fraction of the item that has to be played before it is considered
+/// watched.
+const DEFAULT_SCROBBLE_THRESHOLD: f64 = 0.9;
+
+/// Reports the playback of an item to the server, so that it appears in the
+/// server's active sessions and can be resumed later.
+///
+/// Changes of state are always reported. While the state stays the same the
+/// position is only reported once every [`report_interval`]. Once playback
+/// passes the [`scrobble_threshold`] the item is marked as watched.
+///
+/// [`report_interval`]: PlaybackReporter::set_report_interval
+/// [`scrobble_threshold`]: PlaybackReporter::set_scrobble_threshold
+#[derive(Debug, Clone)]
+pub struct PlaybackReporter<M> {
+    item: M,
+    session_id: String,
+    transcode_session: Option<String>,
+    state: PlaybackState,
+    position: u64,
+    last_report: Option<Instant>,
+    report_interval: Duration,
+    scrobble_threshold: f64,
+    scrobbled: bool,
+}
+
+impl<M: MediaItem> PlaybackReporter<M> {
+    /// Starts reporting the playback of the item. If the item was retrieved
+    /// from a play queue the queue is updated as playback progresses.
+    pub fn new(item: M) -> Self {
+        Self {
+            item,
+            session_id: session_id(),
+            transcode_session: None,
+            state: PlaybackState::Stopped,
+            position: 0,
+            last_report: None,
+            report_interval: DEFAULT_REPORT_INTERVAL,
+            scrobble_threshold: DEFAULT_SCROBBLE_THRESHOLD,
+            scrobbled: false,
+        }
+    }
+
+    pub fn item(&self) -> &M {
+        &self.item
+    }
+
+    /// The identifier the server uses to tell this playback session apart
+    /// from others of the same client.
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// The last reported state.
+    pub fn state(&self) -> PlaybackState {
+        self.state
+    }
+
+    /// The last reported position in milliseconds.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Whether the item was marked as watched during this playback.
+    pub fn is_scrobbled(&self) -> bool {
+        self.scrobbled
+    }
+
+    /// Associates the playback with a transcode session, so that the server
+    /// can stop the session once playback stops.
+    pub fn set_transcode_session(&mut self, session: Option<&TranscodeSession>) {
+        self.transcode_session = session.map(|session| session.session_id().to_owned());
+    }
+
+    pub fn set_report_interval(&mut self, interval: Duration) {
+        self.report_interval = interval;
+    }
+
+    /// Sets the fraction of the item's duration, between 0 and 1, after which
+    /// the item is marked as watched.
+    pub fn set_scrobble_threshold(&mut self, threshold: f64) {
+        self.scrobble_threshold = threshold;
+    }
+
+    /// Updates the playback state and position in milliseconds. The update
+    /// is reported to the server if the state changed or the report interval
+    /// has passed. Returns whether a report was sent.
+    #[tracing::instrument(level = "debug", skip(self), fields(item.id = self.item.rating_key()))]
+    pub async fn update(&mut self, state: PlaybackState, position: u64) -> Result<bool> {
+        let due = match self.last_report {
+            Some(last_report) => {
+                state != self.state || last_report.elapsed() >= self.report_interval
+            }
+            None => true,
+        };
+
+        self.state = state;
+        self.position = position;
+
+        if due {
+            self.report().await?;
+        }
+
+        if !self.scrobbled && self.past_threshold() {
+            self.scrobble().await?;
+        }
+
+        Ok(due)
+    }
+
+    pub async fn playing(&mut self, position: u64) -> Result<bool> {
+        self.update(PlaybackState::Playing, position).await
+    }
+
+    pub async fn paused(&mut self, position: u64) -> Result<bool> {
+        self.update(PlaybackState::Paused, position).await
+    }
+
+    pub async fn buffering(&mut self, position: u64) -> Result<bool> {
+        self.update(PlaybackState::Buffering, position).await
+    }
+
+    /// Reports that playback stopped, unless it already was. A new playback
+    /// session is started if playback resumes afterwards.
+    pub async fn stopped(&mut self, position: u64) -> Result {
+        if self.state == PlaybackState::Stopped {
+            return Ok(());
+        }
+
+        self.update(PlaybackState::Stopped, position).await?;
+        self.session_id = session_id();
+        self.last_report = None;
+        Ok(())
+    }
+
+    /// Sends the current state to the server regardless of when it was last
+    /// reported.
+    #[tracing::instrument(level = "debug", skip(self), fields(item.id = self.item.rating_key()))]
+    pub async fn report(&mut self) -> Result {
+        let metadata = self.item.metadata();
+        let mut params = vec![
+            ("ratingKey", metadata.rating_key.clone()),
+            ("key", metadata.key.clone()),
+            ("state", self.state.to_string()),
+            ("time", self.position.to_string()),
+            ("context", "library".to_owned()),
+        ];
+        if let Some(duration) = metadata.duration {
+            params.push(("duration", duration.to_string()));
+        }
+        if let Some(play_queue_item_id) = metadata.play_queue_item_id {
+            params.push(("playQueueItemID", play_queue_item_id.to_string()));
+        }
+        if let Some(ref transcode_session) = self.transcode_session {
+            params.push(("transcodeSession", transcode_session.clone()));
+        }
+
+        self.item
+            .client()
+            .get(format!(
+                "{SERVER_TIMELINE}?{}",
+                serde_urlencoded::to_string(params)?
+            ))
+            .header("X-Plex-Session-Identifier", &self.session_id)
+            .consume()
+            .await?;
+
+        self.last_report = Some(Instant::now());
+        Ok(())
+    }
+
+    fn past_threshold(&self) -> bool {
+        match self.item.metadata().duration {
+            Some(duration) if duration > 0 => {
+                self.position as f64 >= duration as f64 * self.scrobble_threshold
+            }
+            _ => false,
+        }
+    }
+
+    async fn scrobble(&mut self) -> Result {
+        let query = serde_urlencoded::to_string([
+            ("identifier", "com.plexapp.plugins.library"),
+            ("key", self.item.rating_key()),
+        ])?;
+
+        self.item
+            .client()
+            .get(format!("{SERVER_SCROBBLE}?{query}"))
+            .consume()
+            .await?;
+
+        self.scrobbled = true;
+        Ok(())
+    }
+}
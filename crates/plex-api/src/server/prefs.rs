@@ -11,6 +11,7 @@ use std::mem::discriminant;
 #[derive(Debug, Clone)]
 pub struct Preferences<'a> {
     client: HttpClient,
+    path: String,
     settings: Vec<Setting>,
     changed: Vec<&'a str>,
 }
@@ -18,12 +19,18 @@ pub struct Preferences<'a> {
 impl<'a> Preferences<'a> {
     #[tracing::instrument(level = "debug", skip(client))]
     pub async fn new<C: Into<HttpClient>>(client: C) -> Result<Preferences<'a>> {
-        let client = client.into();
+        Self::with_path(client.into(), SERVER_PREFS.to_owned()).await
+    }
+
+    /// Loads the preferences stored at the path, e.g. the preferences of a
+    /// library section.
+    pub(crate) async fn with_path(client: HttpClient, path: String) -> Result<Preferences<'a>> {
         let mc: MediaContainerWrapper<MediaContainerPreferences> =
-            client.get(SERVER_PREFS).json().await?;
+            client.get(path.as_str()).json().await?;
 
         Ok(Preferences {
             client,
+            path,
             settings: mc.media_container.settings,
             changed: vec![],
         })
@@ -66,12 +73,13 @@ impl<'a> Preferences<'a> {
             params.push((s.id.as_str(), s.value.to_string()));
         }
 
-        let uri = format!("{}?{}", SERVER_PREFS, serde_urlencoded::to_string(params)?);
+        let uri = format!("{}?{}", self.path, serde_urlencoded::to_string(params)?);
 
         self.client.put(uri).consume().await?;
 
         Ok(Preferences {
             client: self.client,
+            path: self.path,
             settings: self.settings,
             changed: vec![],
         })
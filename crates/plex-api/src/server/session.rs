@@ -0,0 +1,81 @@
+use crate::{
+    media_container::server::session::{PlaybackState, Player, SessionLocation, User},
+    transcode::TranscodeSession,
+    url::SERVER_SESSIONS_TERMINATE,
+    Error, HttpClient, Result,
+};
+
+use super::library::{Item, MetadataItem};
+
+/// An item currently being played by one of the users of the server.
+#[derive(Debug, Clone)]
+pub struct Session {
+    client: HttpClient,
+    item: Item,
+}
+
+impl Session {
+    pub(crate) fn new(client: HttpClient, item: Item) -> Self {
+        Self { client, item }
+    }
+
+    /// Returns the item being played.
+    pub fn item(&self) -> &Item {
+        &self.item
+    }
+
+    /// Returns the id used to terminate the session.
+    pub fn id(&self) -> Option<&str> {
+        self.item.metadata().session.as_ref()?.id.as_deref()
+    }
+
+    /// Returns the user playing the item.
+    pub fn user(&self) -> Option<&User> {
+        self.item.metadata().user.as_ref()
+    }
+
+    /// Returns the device playing the item.
+    pub fn player(&self) -> Option<&Player> {
+        self.item.metadata().player.as_deref()
+    }
+
+    pub fn state(&self) -> Option<PlaybackState> {
+        self.player().map(|p| p.state)
+    }
+
+    /// Returns the current playback position in milliseconds.
+    pub fn view_offset(&self) -> Option<u64> {
+        self.item.metadata().view_offset
+    }
+
+    /// Returns the bandwidth used by the session in kbps.
+    pub fn bandwidth(&self) -> Option<u64> {
+        self.item.metadata().session.as_ref()?.bandwidth
+    }
+
+    pub fn location(&self) -> Option<SessionLocation> {
+        self.item.metadata().session.as_ref()?.location
+    }
+
+    /// Returns the transcode session if the item isn't played directly.
+    pub fn transcode_session(&self) -> Option<TranscodeSession> {
+        self.item
+            .metadata()
+            .transcode_session
+            .as_ref()
+            .map(|stats| TranscodeSession::from_stats(self.client.clone(), (**stats).clone()))
+    }
+
+    /// Stops the playback, showing the reason to the user. Requires a Plex
+    /// Pass subscription.
+    #[tracing::instrument(level = "debug", skip(self), fields(session.id = self.id()))]
+    pub async fn terminate(&self, reason: &str) -> Result {
+        let id = self.id().ok_or(Error::SessionIdNotAvailable)?;
+        let query = serde_urlencoded::to_string([("sessionId", id), ("reason", reason)])?;
+
+        self.client
+            .get(format!("{SERVER_SESSIONS_TERMINATE}?{query}"))
+            .consume()
+            .await
+    }
+}
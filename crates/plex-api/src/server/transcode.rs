@@ -25,7 +25,7 @@ use crate::{
         server::{
             library::{
                 AudioCodec, AudioStream, ContainerFormat, Decision, Media as MediaMetadata,
-                Metadata, Protocol, Stream, SubtitleCodec, VideoCodec, VideoStream,
+                Metadata, Protocol, Stream, SubtitleCodec, SubtitleStream, VideoCodec, VideoStream,
             },
             Feature,
         },
@@ -302,6 +302,37 @@ pub trait TranscodeOptions {
     ) -> HashMap<String, String>;
 }
 
+/// The subtitles to show for a part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubtitleSelection {
+    /// Don't show any subtitles.
+    None,
+    /// Show the subtitle stream with this id.
+    Stream(String),
+}
+
+impl SubtitleSelection {
+    /// The stream id as the server expects it, `0` meaning no subtitles.
+    pub(crate) fn stream_id(&self) -> &str {
+        match self {
+            SubtitleSelection::None => "0",
+            SubtitleSelection::Stream(id) => id,
+        }
+    }
+}
+
+impl From<&SubtitleStream> for SubtitleSelection {
+    fn from(stream: &SubtitleStream) -> Self {
+        SubtitleSelection::Stream(stream.id.clone())
+    }
+}
+
+impl From<Option<&SubtitleStream>> for SubtitleSelection {
+    fn from(stream: Option<&SubtitleStream>) -> Self {
+        stream.map_or(SubtitleSelection::None, Into::into)
+    }
+}
+
 /// Defines the media formats suitable for transcoding video. The server uses
 /// these settings to choose a format to transcode to.
 ///
@@ -340,6 +371,12 @@ pub struct VideoTranscodeOptions {
     pub audio_limitations: Vec<Limitation<AudioCodec, AudioSetting>>,
     /// Supported subtitle codecs.
     pub subtitle_codecs: Vec<SubtitleCodec>,
+    /// The id of the audio stream to use. By default the server uses the
+    /// stream selected for the part.
+    pub audio_stream: Option<String>,
+    /// The subtitles to use. By default the server uses the stream selected
+    /// for the part.
+    pub subtitle_stream: Option<SubtitleSelection>,
 }
 
 impl Default for VideoTranscodeOptions {
@@ -356,6 +393,8 @@ impl Default for VideoTranscodeOptions {
             audio_codecs: vec![AudioCodec::Aac, AudioCodec::Mp3],
             audio_limitations: Default::default(),
             subtitle_codecs: Default::default(),
+            audio_stream: None,
+            subtitle_stream: None,
         }
     }
 }
@@ -383,6 +422,14 @@ impl TranscodeOptions for VideoTranscodeOptions {
             query = query.param("audioBoost", boost.to_string());
         }
 
+        if let Some(ref stream) = self.audio_stream {
+            query = query.param("audioStreamID", stream);
+        }
+
+        if let Some(ref selection) = self.subtitle_stream {
+            query = query.param("subtitleStreamID", selection.stream_id());
+        }
+
         let video_codecs = self
             .video_codecs
             .iter()
@@ -568,7 +615,7 @@ impl TranscodeOptions for MusicTranscodeOptions {
 }
 
 /// Generates a unique session id. This appears to just be any random string.
-fn session_id() -> String {
+pub(crate) fn session_id() -> String {
     Uuid::new_v4().as_simple().to_string()
 }
 
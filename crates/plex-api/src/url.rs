@@ -7,7 +7,9 @@ pub const MYPLEX_CLAIM_TOKEN_PATH: &str = "/api/claim/token.json";
 pub const MYPLEX_PRIVACY_PATH: &str = "/api/v2/user/privacy";
 pub const MYPLEX_WEBHOOKS_PATH: &str = "/api/v2/user/webhooks";
 pub const MYPLEX_DEVICES: &str = "/devices.xml";
-pub const MYPLEX_RESOURCES: &str = "/api/resources"; // TODO: migrate to /api/v2/resources.json
+pub const MYPLEX_DEVICE: &str = "/devices/{id}.xml";
+pub const MYPLEX_DEVICE_SYNC_ITEMS: &str = "/devices/{client_identifier}/sync_items";
+pub const MYPLEX_RESOURCES: &str = "/api/v2/resources";
 pub const MYPLEX_FEATURES: &str = "/api/v2/features";
 pub const MYPLEX_COMPANIONS: &str = "/api/v2/companions";
 pub const MYPLEX_PROVIDERS: &str = "/media/providers";
@@ -43,7 +45,32 @@ pub const SERVER_SYSTEM_PROXY: &str = "/system/proxy";
 pub const SERVER_SCROBBLE: &str = "/:/scrobble";
 pub const SERVER_UNSCROBBLE: &str = "/:/unscrobble";
 pub const SERVER_TIMELINE: &str = "/:/timeline";
+pub const SERVER_HUBS: &str = "/hubs";
+pub const SERVER_HUBS_SEARCH: &str = "/hubs/search";
+pub const SERVER_CONTINUE_WATCHING: &str = "/hubs/continueWatching/items";
+pub const SERVER_ON_DECK: &str = "/library/onDeck";
+pub const SERVER_RECENTLY_ADDED: &str = "/library/recentlyAdded";
+pub const SERVER_SESSIONS: &str = "/status/sessions";
+pub const SERVER_SESSIONS_TERMINATE: &str = "/status/sessions/terminate";
+pub const SERVER_HISTORY: &str = "/status/sessions/history/all";
+pub const SERVER_NOTIFICATIONS: &str = "/:/eventsource/notifications";
+pub const SERVER_ACTIVITIES: &str = "/activities";
+pub const SERVER_BUTLER: &str = "/butler";
+pub const SERVER_LIBRARY_SECTIONS: &str = "/library/sections";
+pub const SERVER_AGENTS: &str = "/system/agents";
+pub const SERVER_SCANNERS: &str = "/system/scanners";
+pub const SERVER_PLAYLISTS: &str = "/playlists";
+pub const SERVER_COLLECTIONS: &str = "/library/collections";
+pub const SERVER_LIBRARY_PART: &str = "/library/parts/{part_id}";
+pub const SERVER_PLAY_QUEUES: &str = "/playQueues";
+pub const SERVER_LIBRARIES_REFRESH: &str = "/library/sections/all/refresh";
+pub const SERVER_CLEAN_BUNDLES: &str = "/library/clean/bundles";
+pub const SERVER_OPTIMIZE_DATABASE: &str = "/library/optimize";
 
 pub const CLIENT_RESOURCES: &str = "/resources";
+pub const CLIENT_PLAYBACK: &str = "/player/playback";
+pub const CLIENT_NAVIGATION: &str = "/player/navigation";
+pub const CLIENT_MIRROR: &str = "/player/mirror/details";
+pub const CLIENT_TIMELINE: &str = "/player/timeline";
 
 pub const MYPLEX_DISCOVER_API_BASE_URL: &str = "https://discover.provider.plex.tv/";
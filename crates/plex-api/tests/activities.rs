@@ -0,0 +1,100 @@
+mod fixtures;
+
+mod offline {
+    use super::fixtures::offline::{server::*, Mocked};
+    use httpmock::Method::{DELETE, GET, POST};
+    use plex_api::{
+        url::{SERVER_ACTIVITIES, SERVER_BUTLER},
+        Server,
+    };
+
+    #[plex_api_test_helper::offline_test]
+    async fn activities(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_ACTIVITIES);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/activities/scanning.json");
+        });
+
+        let activities = server.activities().await.unwrap();
+        m.assert();
+        assert_eq!(activities.len(), 2);
+
+        assert_eq!(activities[0].activity_type, "butler.backgroundTask");
+        assert!(!activities[0].cancellable);
+        assert!(activities[0].context.is_none());
+
+        let scan = &activities[1];
+        assert_eq!(scan.title, "Scanning Movies");
+        assert_eq!(scan.subtitle.as_deref(), Some("Sintel"));
+        assert!(scan.cancellable);
+        assert_eq!(
+            scan.context.as_ref().unwrap().library_section_id.as_deref(),
+            Some("1")
+        );
+
+        let m = mock_server.mock(|when, then| {
+            when.method(DELETE)
+                .path(format!("{SERVER_ACTIVITIES}/{}", scan.uuid));
+            then.status(200);
+        });
+
+        server.cancel_activity(&scan.uuid).await.unwrap();
+        m.assert();
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn butler_tasks(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_BUTLER);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/butler.json");
+        });
+
+        let tasks = server.butler_tasks().await.unwrap();
+        m.assert();
+        assert_eq!(
+            tasks.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            vec!["BackupDatabase", "DeepMediaAnalysis", "RefreshLocalMedia"]
+        );
+        assert_eq!(tasks[0].interval, 3);
+        assert!(tasks[0].enabled);
+        assert!(!tasks[1].enabled);
+        assert!(tasks[2].schedule_randomized);
+
+        let m = mock_server.mock(|when, then| {
+            when.method(POST).path("/butler/BackupDatabase");
+            then.status(200);
+        });
+
+        server.start_butler_task("BackupDatabase").await.unwrap();
+        m.assert();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(DELETE).path("/butler/BackupDatabase");
+            then.status(200);
+        });
+
+        server.stop_butler_task("BackupDatabase").await.unwrap();
+        m.assert();
+    }
+}
+
+mod online {
+    use super::fixtures::online::server::*;
+    use plex_api::Server;
+
+    #[plex_api_test_helper::online_test]
+    async fn butler_tasks(#[future] server: Server) {
+        let tasks = server.butler_tasks().await.unwrap();
+        assert!(tasks.iter().any(|t| t.name == "BackupDatabase"));
+
+        server.activities().await.unwrap();
+    }
+}
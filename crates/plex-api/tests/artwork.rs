@@ -0,0 +1,118 @@
+mod fixtures;
+
+mod offline {
+    use super::fixtures::offline::{server::*, Mocked};
+    use futures::io::Cursor;
+    use httpmock::{
+        Method::{GET, POST, PUT},
+        MockServer,
+    };
+    use plex_api::{
+        library::{ArtworkKind, Item, MetadataItemWithArtwork, Movie},
+        Server,
+    };
+
+    async fn movie(server: &Server, mock_server: &MockServer) -> Movie {
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/metadata/108");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/metadata_108.json");
+        });
+
+        let item = server.item_by_id("108").await.unwrap();
+        m.assert();
+        m.delete();
+
+        match item {
+            Item::Movie(movie) => movie,
+            _ => panic!("Unexpected item type"),
+        }
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn list_and_select(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let movie = movie(&server, &mock_server).await;
+
+        let m_posters = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/metadata/108/posters");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/artwork/posters_108.json");
+        });
+
+        let posters = movie.artwork(ArtworkKind::Poster).await.unwrap();
+        m_posters.assert();
+        assert_eq!(posters.len(), 3);
+        assert!(posters[0].selected);
+        assert_eq!(posters[1].provider.as_deref(), Some("tmdb"));
+        assert!(posters[2].provider.is_none());
+
+        let m_select = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/library/metadata/108/poster")
+                .query_param(
+                    "url",
+                    "https://image.tmdb.org/t/p/original/interstate60.jpg",
+                );
+            then.status(200);
+        });
+
+        movie
+            .select_artwork(ArtworkKind::Poster, &posters[1])
+            .await
+            .unwrap();
+        m_select.assert();
+
+        let m_themes = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/metadata/108/themes");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/artwork/themes_108.json");
+        });
+
+        let themes = movie.artwork(ArtworkKind::Theme).await.unwrap();
+        m_themes.assert();
+        assert_eq!(themes[0].rating_key, "upload://themes/d41d8c");
+        assert!(themes[0].thumb.is_none());
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn upload(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let movie = movie(&server, &mock_server).await;
+
+        let m_url = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/library/metadata/108/arts")
+                .query_param("url", "https://example.com/background.jpg");
+            then.status(200);
+        });
+
+        movie
+            .upload_artwork_url(
+                ArtworkKind::Background,
+                "https://example.com/background.jpg",
+            )
+            .await
+            .unwrap();
+        m_url.assert();
+
+        let m_upload = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/library/metadata/108/themes")
+                .body("not really an mp3");
+            then.status(200);
+        });
+
+        movie
+            .upload_artwork(
+                ArtworkKind::Theme,
+                Cursor::new(b"not really an mp3".to_vec()),
+            )
+            .await
+            .unwrap();
+        m_upload.assert();
+    }
+}
@@ -0,0 +1,309 @@
+mod fixtures;
+
+mod offline {
+    use super::fixtures::offline::{server::*, Mocked};
+    use futures::{io::Cursor, TryStreamExt};
+    use httpmock::{
+        Method::{DELETE, GET, POST, PUT},
+        MockServer,
+    };
+    use plex_api::{
+        library::{
+            Collection, CollectionMode, CollectionSort, Library, MetadataItem, Movie, MovieLibrary,
+            PagingOptions,
+        },
+        url::SERVER_COLLECTIONS,
+        Error, Server,
+    };
+
+    fn movie_library(server: &Server) -> MovieLibrary {
+        match server.libraries().remove(0) {
+            Library::Movie(library) => library,
+            _ => panic!("Unexpected library type"),
+        }
+    }
+
+    async fn movies(server: &Server, mock_server: &MockServer) -> Vec<Movie> {
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/sections/1/all");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/movie_library.json");
+        });
+
+        let movies = movie_library(server).movies().await.unwrap();
+        m.assert();
+        m.delete();
+        movies
+    }
+
+    async fn create_collection(server: &Server, mock_server: &MockServer) -> Collection<Movie> {
+        let movies = movies(server, mock_server).await;
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path(SERVER_COLLECTIONS)
+                .query_param("type", "1")
+                .query_param("title", "Blender Foundation")
+                .query_param("smart", "0")
+                .query_param("sectionId", "1")
+                .query_param(
+                    "uri",
+                    "server://machine_id3/com.plexapp.plugins.library/library/metadata/55,57",
+                );
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/collections/created.json");
+        });
+
+        let blender: Vec<Movie> = movies
+            .into_iter()
+            .filter(|m| ["55", "57"].contains(&m.rating_key()))
+            .collect();
+        let collection = server
+            .create_collection(&movie_library(server), "Blender Foundation", &blender)
+            .await
+            .unwrap();
+        m.assert();
+        m.delete();
+        collection
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn create(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let collection = create_collection(&server, &mock_server).await;
+        assert_eq!(collection.rating_key(), "175");
+        assert_eq!(collection.title(), "Blender Foundation");
+        assert!(!collection.is_smart());
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn create_empty(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(POST).path(SERVER_COLLECTIONS);
+            then.status(200);
+        });
+
+        let result = server
+            .create_collection(&movie_library(&server), "Empty", &[])
+            .await;
+        assert!(
+            matches!(result, Err(Error::NoItemsProvided)),
+            "unexpected result: {result:?}"
+        );
+        m.assert_calls(0);
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn create_smart(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path(SERVER_COLLECTIONS)
+                .query_param("type", "1")
+                .query_param("title", "Recent Movies")
+                .query_param("smart", "1")
+                .query_param("sectionId", "1")
+                .query_param(
+                    "uri",
+                    "server://machine_id3/com.plexapp.plugins.library/library/sections/1/all?type=1&year%3E%3E=2009",
+                );
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/collections/created_smart.json");
+        });
+
+        // Filters are validated against the library before the collection
+        // is created.
+        let m_filters = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/library/sections/1/filters")
+                .query_param("type", "1");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/library/movie_filters.json");
+        });
+
+        let search = movie_library(&server).search().year(2010..);
+        let collection = server
+            .create_smart_collection("Recent Movies", &search)
+            .await
+            .unwrap();
+        m_filters.assert();
+        m.assert();
+        assert_eq!(collection.rating_key(), "176");
+        assert!(collection.is_smart());
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn edit(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let collection = create_collection(&server, &mock_server).await;
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/library/metadata/175/prefs")
+                .query_param("collectionMode", "-1");
+            then.status(200);
+        });
+        collection.set_mode(CollectionMode::Default).await.unwrap();
+        m.assert();
+        m.delete();
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/library/metadata/175/prefs")
+                .query_param("collectionSort", "1");
+            then.status(200);
+        });
+        collection
+            .set_sort(CollectionSort::Alphabetical)
+            .await
+            .unwrap();
+        m.assert();
+        m.delete();
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/library/metadata/175/posters")
+                .body("not really a jpeg");
+            then.status(200);
+        });
+        collection
+            .upload_poster(Cursor::new(b"not really a jpeg".to_vec()))
+            .await
+            .unwrap();
+        m.assert();
+        m.delete();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(DELETE).path("/library/collections/175");
+            then.status(200);
+        });
+        collection.delete().await.unwrap();
+        m.assert();
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn children_stream(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let collection = create_collection(&server, &mock_server).await;
+
+        let m_page1 = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/library/collections/175/children")
+                .header("X-Plex-Container-Start", "0")
+                .header("X-Plex-Container-Size", "2");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/movie_library_page1.json");
+        });
+        let m_page2 = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/library/collections/175/children")
+                .header("X-Plex-Container-Start", "2")
+                .header("X-Plex-Container-Size", "2");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/movie_library_page2.json");
+        });
+
+        let stream = collection
+            .children_stream(PagingOptions {
+                page_size: 2,
+                prefetch: 1,
+            })
+            .await
+            .unwrap();
+        assert_eq!(stream.total_size(), 4);
+        m_page1.assert();
+        m_page2.assert_calls(0);
+
+        let movies: Vec<Movie> = stream.try_collect().await.unwrap();
+        m_page2.assert();
+        assert_eq!(
+            movies.iter().map(|m| m.rating_key()).collect::<Vec<_>>(),
+            vec!["55", "56", "108", "57"]
+        );
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn items(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let collection = create_collection(&server, &mock_server).await;
+        let movies = movies(&server, &mock_server).await;
+
+        let m = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/library/collections/175/items")
+                .query_param(
+                    "uri",
+                    "server://machine_id3/com.plexapp.plugins.library/library/metadata/108",
+                );
+            then.status(200);
+        });
+        server
+            .add_to_collection(&collection, &movies[2..3])
+            .await
+            .unwrap();
+        m.assert();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(DELETE)
+                .path("/library/collections/175/items/108");
+            then.status(200);
+        });
+        server
+            .remove_from_collection(&collection, &movies[2])
+            .await
+            .unwrap();
+        m.assert();
+    }
+}
+
+mod online {
+    use super::fixtures::online::server::*;
+    use plex_api::{
+        library::{CollectionMode, Library, MetadataItem},
+        Server,
+    };
+
+    #[plex_api_test_helper::online_test_non_shared_server]
+    async fn create_and_edit(#[future] server: Server) {
+        let library = server
+            .libraries()
+            .into_iter()
+            .find_map(|l| match l {
+                Library::Movie(library) => Some(library),
+                _ => None,
+            })
+            .expect("no movie library found");
+        let movies = library.movies().await.unwrap();
+
+        let collection = server
+            .create_collection(&library, "Test Collection", &movies[..1])
+            .await
+            .unwrap();
+        assert_eq!(collection.title(), "Test Collection");
+
+        server
+            .add_to_collection(&collection, &movies[1..2])
+            .await
+            .unwrap();
+        assert_eq!(collection.children().await.unwrap().len(), 2);
+
+        server
+            .remove_from_collection(&collection, &movies[0])
+            .await
+            .unwrap();
+        assert_eq!(collection.children().await.unwrap().len(), 1);
+
+        collection.set_mode(CollectionMode::Hide).await.unwrap();
+        collection.delete().await.unwrap();
+    }
+}
@@ -0,0 +1,160 @@
+mod fixtures;
+
+mod offline {
+    use super::fixtures::offline::{client::*, Mocked};
+    use httpmock::Method::GET;
+    use plex_api::{
+        device::DeviceConnection,
+        gdm::{DeviceType, Discovery},
+        media_container::player::{DeviceClass, Player as PlayerResources, ProtocolCapability},
+        url::SERVER_MEDIA_PROVIDERS,
+        HttpClient, PlayMediaRequest, PlaybackType, Receiver, ReceiverHandler,
+    };
+    use std::{
+        net::{TcpListener, UdpSocket},
+        thread,
+        time::Duration,
+    };
+
+    struct IdleHandler;
+
+    impl ReceiverHandler for IdleHandler {
+        fn play_media(&mut self, _request: PlayMediaRequest) {}
+        fn play(&mut self, _playback_type: PlaybackType) {}
+        fn pause(&mut self, _playback_type: PlaybackType) {}
+        fn stop(&mut self, _playback_type: PlaybackType) {}
+        fn seek_to(&mut self, _playback_type: PlaybackType, _offset: u64) {}
+        fn timelines(&self) -> Vec<plex_api::media_container::player::Timeline> {
+            vec![]
+        }
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn discover_players(client_authenticated: Mocked<HttpClient>) {
+        let (client, _) = client_authenticated.split();
+
+        let receiver = Receiver::start(
+            TcpListener::bind("127.0.0.1:0").unwrap(),
+            PlayerResources {
+                machine_identifier: "receiver_id".to_owned(),
+                product: "Kiosk".to_owned(),
+                protocol: "plex".to_owned(),
+                protocol_version: 1,
+                device_class: DeviceClass::Stb,
+                platform: "Linux".to_owned(),
+                platform_version: "6.1".to_owned(),
+                title: "Lobby Screen".to_owned(),
+                protocol_capabilities: vec![
+                    ProtocolCapability::Playback,
+                    ProtocolCapability::Timeline,
+                ],
+            },
+            IdleHandler,
+        )
+        .unwrap();
+        let responder = receiver
+            .advertise(UdpSocket::bind("127.0.0.1:0").unwrap())
+            .unwrap();
+
+        let devices = Discovery::players()
+            .set_target(responder.local_addr())
+            .set_timeout(Duration::from_millis(500))
+            .discover()
+            .await
+            .unwrap();
+        assert_eq!(devices.len(), 1);
+
+        let device = &devices[0];
+        assert_eq!(device.device_type, DeviceType::Player);
+        assert_eq!(device.resource_identifier, "receiver_id");
+        assert_eq!(device.name, "Lobby Screen");
+        assert_eq!(device.port, receiver.local_addr().port());
+        assert_eq!(device.product.as_deref(), Some("Kiosk"));
+        assert_eq!(device.device_class, Some(DeviceClass::Stb));
+        assert_eq!(
+            device.protocol_capabilities,
+            vec![ProtocolCapability::Playback, ProtocolCapability::Timeline]
+        );
+        assert_eq!(device.url(), format!("http://{}", receiver.local_addr()));
+
+        let DeviceConnection::Player(player) = device.connect(client).await.unwrap() else {
+            panic!("expected a player");
+        };
+        assert_eq!(player.machine_identifier(), "receiver_id");
+        assert_eq!(
+            player.client().x_plex_target_client_identifier,
+            "receiver_id"
+        );
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn discover_servers(client_authenticated: Mocked<HttpClient>) {
+        let (client, mock_server) = client_authenticated.split();
+
+        // Stands in for a server, which answers with a slightly different set
+        // of headers than players.
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let target = socket.local_addr().unwrap();
+        let port = mock_server.address().port();
+        thread::spawn(move || {
+            let mut buf = [0; 1024];
+            let (len, from) = socket.recv_from(&mut buf).unwrap();
+            assert!(buf[..len].starts_with(b"M-SEARCH * HTTP/1.1"));
+
+            // Answers that aren't from Plex devices are ignored.
+            socket
+                .send_to(b"HTTP/1.1 404 Not Found\r\n\r\n", from)
+                .unwrap();
+            let response = format!(
+                "HTTP/1.0 200 OK\r\nContent-Type: plex/media-server\r\n\
+                 Resource-Identifier: machine_id3\r\nName: Basement\r\nPort: {port}\r\n\
+                 Updated-At: 1690000000\r\nVersion: 1.32.5.7349-8f4248874\r\n\r\n"
+            );
+            socket.send_to(response.as_bytes(), from).unwrap();
+        });
+
+        let devices = Discovery::servers()
+            .set_target(target)
+            .set_timeout(Duration::from_millis(500))
+            .discover()
+            .await
+            .unwrap();
+        assert_eq!(devices.len(), 1);
+
+        let device = &devices[0];
+        assert_eq!(device.device_type, DeviceType::Server);
+        assert_eq!(device.resource_identifier, "machine_id3");
+        assert_eq!(device.name, "Basement");
+        assert_eq!(device.version.as_deref(), Some("1.32.5.7349-8f4248874"));
+        assert!(device.protocol_capabilities.is_empty());
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_MEDIA_PROVIDERS);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/providers_unclaimed.json");
+        });
+        let DeviceConnection::Server(server) = device.connect(client).await.unwrap() else {
+            panic!("expected a server");
+        };
+        m.assert();
+        assert_eq!(server.machine_identifier(), "machine_id3");
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn discover_nothing() {
+        // Nothing listens on the socket's address once it's dropped.
+        let target = UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let devices = Discovery::players()
+            .set_target(target)
+            .set_timeout(Duration::from_millis(200))
+            .discover()
+            .await
+            .unwrap();
+        assert!(devices.is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+mod fixtures;
+
+mod offline {
+    use super::fixtures::offline::{server::*, Mocked};
+    use futures::TryStreamExt;
+    use httpmock::Method::GET;
+    use plex_api::{
+        history::{HistoryEntry, HistoryOptions},
+        library::{Item, MetadataItem, PagingOptions},
+        url::SERVER_HISTORY,
+        Error, Server,
+    };
+    use time::OffsetDateTime;
+
+    #[plex_api_test_helper::offline_test]
+    async fn history(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path(SERVER_HISTORY)
+                .query_param("sort", "viewedAt:desc")
+                .query_param("accountID", "1")
+                .query_param("librarySectionID", "1")
+                .query_param("viewedAt>", "1693526400")
+                .query_param("viewedAt<", "1696118401")
+                .header("X-Plex-Container-Start", "0");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/history.json");
+        });
+
+        let stream = server
+            .history(
+                HistoryOptions {
+                    account_id: Some(1),
+                    library_section_id: Some("1".to_owned()),
+                    viewed_after: Some(OffsetDateTime::from_unix_timestamp(1693526400).unwrap()),
+                    viewed_before: Some(OffsetDateTime::from_unix_timestamp(1696118401).unwrap()),
+                    ..Default::default()
+                },
+                PagingOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stream.total_size(), 3);
+
+        let entries: Vec<HistoryEntry> = stream.try_collect().await.unwrap();
+        m.assert();
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].title(), "Big Buck Bunny");
+        assert_eq!(entries[0].rating_key(), "55");
+        assert_eq!(entries[0].account_id(), Some(1));
+        assert_eq!(entries[0].device_id(), Some(3));
+        assert_eq!(
+            entries[0].viewed_at(),
+            Some(OffsetDateTime::from_unix_timestamp(1696118400).unwrap())
+        );
+        assert_eq!(
+            entries[1]
+                .metadata()
+                .grand_parent
+                .grandparent_title
+                .as_deref(),
+            Some("The 100")
+        );
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/metadata/55");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/movie_library.json");
+        });
+
+        let item = entries[0].item().await.unwrap();
+        m.assert();
+        m.delete();
+        assert!(matches!(item, Item::Movie(_)));
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/metadata/57");
+            then.status(404);
+        });
+
+        let result = entries[2].item().await;
+        m.assert();
+        assert!(matches!(result, Err(Error::ItemNotFound)));
+    }
+}
+
+mod online {
+    use super::fixtures::online::server::*;
+    use futures::TryStreamExt;
+    use plex_api::{history::HistoryOptions, library::PagingOptions, Server};
+
+    #[plex_api_test_helper::online_test]
+    async fn history(#[future] server: Server) {
+        let stream = server
+            .history(HistoryOptions::default(), PagingOptions::default())
+            .await
+            .unwrap();
+        let total_size = stream.total_size();
+        let entries: Vec<_> = stream.try_collect().await.unwrap();
+        assert_eq!(entries.len(), total_size as usize);
+    }
+}
@@ -0,0 +1,177 @@
+mod fixtures;
+
+fn map<I, F, R>(items: &[I], mapper: F) -> Vec<R>
+where
+    F: FnMut(&I) -> R,
+{
+    items.iter().map(mapper).collect()
+}
+
+mod offline {
+    use super::fixtures::offline::{server::*, Mocked};
+    use super::map;
+    use httpmock::Method::GET;
+    use plex_api::{
+        library::{Item, MetadataItem},
+        url::{SERVER_HUBS, SERVER_HUBS_SEARCH, SERVER_ON_DECK},
+        Server,
+    };
+
+    #[plex_api_test_helper::offline_test]
+    async fn home_hubs(#[future] server_anonymous: Mocked<Server>) {
+        let (server, mock_server) = server_anonymous.split();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_HUBS);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/hubs/home.json");
+        });
+
+        let hubs = server.hubs().await.unwrap();
+        m.assert();
+
+        assert_eq!(
+            map(&hubs, |h| h.identifier().to_owned()),
+            vec!["home.continue", "home.movies.recent"]
+        );
+        assert_eq!(hubs[0].title(), "Continue Watching");
+        assert!(!hubs[0].has_more());
+
+        let items = hubs[0].items();
+        assert_eq!(items.len(), 1);
+        match &items[0] {
+            Item::Movie(movie) => {
+                assert_eq!(movie.title(), "Big Buck Bunny");
+                assert_eq!(movie.metadata().view_offset, Some(120000));
+            }
+            item => panic!("Unexpected item: {item:?}"),
+        }
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/hubs/home/recentlyAdded")
+                .query_param("type", "1")
+                .query_param("sectionID", "1");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/movie_library.json");
+        });
+
+        assert!(hubs[1].has_more());
+        let items = hubs[1].all_items().await.unwrap();
+        m.assert();
+        assert_eq!(
+            map(&items, |i| i.rating_key().to_owned()),
+            vec!["55", "56", "108", "57"]
+        );
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn library_hubs(#[future] server_anonymous: Mocked<Server>) {
+        let (server, mock_server) = server_anonymous.split();
+        let library = &server.libraries()[0];
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET).path("/hubs/sections/1");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/hubs/home.json");
+        });
+
+        let hubs = library.hubs().await.unwrap();
+        m.assert();
+        m.delete();
+        assert_eq!(hubs.len(), 2);
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/sections/1/onDeck");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/movie_library.json");
+        });
+
+        let items = library.on_deck().await.unwrap();
+        m.assert();
+        assert_eq!(items.len(), 4);
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn on_deck(#[future] server_anonymous: Mocked<Server>) {
+        let (server, mock_server) = server_anonymous.split();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_ON_DECK);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/movie_library.json");
+        });
+
+        let items = server.on_deck().await.unwrap();
+        m.assert();
+        assert_eq!(
+            map(&items, |i| i.title().to_owned()),
+            vec![
+                "Big Buck Bunny",
+                "Elephants Dream",
+                "Interstate 60",
+                "Sintel"
+            ]
+        );
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn search(#[future] server_anonymous: Mocked<Server>) {
+        let (server, mock_server) = server_anonymous.split();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path(SERVER_HUBS_SEARCH)
+                .query_param("query", "sintel")
+                .query_param("limit", "5");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/hubs/search.json");
+        });
+
+        let hubs = server.search_hubs("sintel", Some(5)).await.unwrap();
+        m.assert();
+
+        assert_eq!(
+            map(&hubs, |h| h.hub_type().to_owned()),
+            vec!["movie", "genre"]
+        );
+
+        let items = hubs[0].items();
+        assert_eq!(map(&items, |i| i.title().to_owned()), vec!["Sintel"]);
+        assert!(items[0].metadata().score.is_some());
+
+        assert!(hubs[1].items().is_empty());
+        assert_eq!(hubs[1].metadata().directories.len(), 1);
+    }
+}
+
+mod online {
+    use super::fixtures::online::server::*;
+    use plex_api::{library::MetadataItem, Server};
+
+    #[plex_api_test_helper::online_test]
+    async fn hubs(#[future] server: Server) {
+        let hubs = server.hubs().await.unwrap();
+        assert!(!hubs.is_empty());
+
+        let libraries = server.libraries();
+        let hubs = libraries[0].hubs().await.unwrap();
+        assert!(!hubs.is_empty());
+    }
+
+    #[plex_api_test_helper::online_test]
+    async fn search(#[future] server: Server) {
+        let hubs = server.search_hubs("sintel", None).await.unwrap();
+        let movies = hubs
+            .iter()
+            .find(|h| h.hub_type() == "movie")
+            .expect("no movies found");
+        assert!(movies.items().iter().any(|i| i.title() == "Sintel"));
+    }
+}
@@ -0,0 +1,206 @@
+mod fixtures;
+
+fn map<I, F, R>(items: &[I], mapper: F) -> Vec<R>
+where
+    F: FnMut(&I) -> R,
+{
+    items.iter().map(mapper).collect()
+}
+
+mod offline {
+    use super::fixtures::offline::{server::*, Mocked};
+    use super::map;
+    use httpmock::{Method::GET, MockServer};
+    use plex_api::{
+        library::{Library, MetadataItem, MovieLibrary, Operator},
+        media_container::server::library::SortDirection,
+        Error, Server,
+    };
+
+    fn movie_library(server: &Server) -> MovieLibrary {
+        match server.libraries().remove(0) {
+            Library::Movie(lib) => lib,
+            library => panic!("Unexpected library: {library:?}"),
+        }
+    }
+
+    fn mock_filters(mock_server: &MockServer) -> httpmock::Mock<'_> {
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/library/sections/1/filters")
+                .query_param("type", "1");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/library/movie_filters.json");
+        })
+    }
+
+    fn mock_sorts(mock_server: &MockServer) -> httpmock::Mock<'_> {
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/library/sections/1/sorts")
+                .query_param("type", "1");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/library/movie_sorts.json");
+        })
+    }
+
+    fn mock_genres(mock_server: &MockServer) -> httpmock::Mock<'_> {
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/library/sections/1/genre")
+                .query_param("type", "1");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/library/movie_genres.json");
+        })
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn search_movies(#[future] server_anonymous: Mocked<Server>) {
+        let (server, mock_server) = server_anonymous.split();
+        let library = movie_library(&server);
+
+        let m_filters = mock_filters(&mock_server);
+        let m_sorts = mock_sorts(&mock_server);
+        let m_genres = mock_genres(&mock_server);
+        let m_search = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/library/sections/1/all")
+                .query_param("type", "1")
+                .query_param("genre", "6")
+                .query_param("year>>", "2004")
+                .query_param("year<<", "2011")
+                .query_param("sort", "year:desc");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/library/movie_search.json");
+        });
+
+        let movies = library
+            .search()
+            .genre("animation")
+            .year(2005..=2010)
+            .sort("year", SortDirection::Desc)
+            .execute()
+            .await
+            .expect("failed to search the library");
+
+        m_filters.assert();
+        m_sorts.assert();
+        m_genres.assert();
+        m_search.assert();
+
+        assert_eq!(
+            map(&movies, |e| e.title().to_owned()),
+            vec!["Sintel", "Big Buck Bunny", "Elephants Dream"]
+        );
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn search_unsupported_filter(#[future] server_anonymous: Mocked<Server>) {
+        let (server, mock_server) = server_anonymous.split();
+        let library = movie_library(&server);
+
+        let m_filters = mock_filters(&mock_server);
+
+        let result = library
+            .search()
+            .unwatched()
+            .filter("mood", Operator::Is, "happy")
+            .execute()
+            .await;
+
+        m_filters.assert();
+        assert!(
+            matches!(result, Err(Error::SearchFilterNotSupported(ref filter)) if filter == "mood"),
+            "unexpected result: {result:?}"
+        );
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn search_unknown_filter_value(#[future] server_anonymous: Mocked<Server>) {
+        let (server, mock_server) = server_anonymous.split();
+        let library = movie_library(&server);
+
+        let m_filters = mock_filters(&mock_server);
+        let m_genres = mock_genres(&mock_server);
+
+        let result = library.search().genre("Western").execute().await;
+
+        m_filters.assert();
+        m_genres.assert();
+        assert!(
+            matches!(
+                result,
+                Err(Error::SearchFilterValueNotFound { ref filter, ref value })
+                    if filter == "genre" && value == "Western"
+            ),
+            "unexpected result: {result:?}"
+        );
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn search_unsupported_sort(#[future] server_anonymous: Mocked<Server>) {
+        let (server, mock_server) = server_anonymous.split();
+        let library = movie_library(&server);
+
+        let m_sorts = mock_sorts(&mock_server);
+
+        let result = library
+            .search()
+            .sort("episode.index", SortDirection::Asc)
+            .execute()
+            .await;
+
+        m_sorts.assert();
+        assert!(
+            matches!(result, Err(Error::SearchSortNotSupported(ref sort)) if sort == "episode.index"),
+            "unexpected result: {result:?}"
+        );
+    }
+}
+
+mod online {
+    use super::fixtures::online::server::*;
+    use super::map;
+    use plex_api::{
+        library::{Library, MetadataItem},
+        media_container::server::library::SortDirection,
+        Error, Server,
+    };
+
+    #[plex_api_test_helper::online_test]
+    async fn search_movies(#[future] server: Server) {
+        let libraries = server.libraries();
+
+        let library = if let Library::Movie(lib) = libraries.first().unwrap() {
+            lib
+        } else {
+            panic!("Unexpected library type");
+        };
+
+        let movies = library.movies().await.unwrap();
+        let sorted = library
+            .search()
+            .sort("titleSort", SortDirection::Asc)
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(
+            map(&sorted, |e| e.rating_key().to_owned()),
+            map(&movies, |e| e.rating_key().to_owned())
+        );
+
+        let result = library
+            .search()
+            .genre("this genre does not exist")
+            .execute()
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::SearchFilterValueNotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,324 @@
+mod fixtures;
+
+mod offline {
+    use super::fixtures::offline::{server::*, Mocked};
+    use httpmock::Method::{DELETE, GET, POST, PUT};
+    use plex_api::{
+        library::NewLibrary,
+        media_container::{
+            preferences::Value,
+            server::library::{LibraryType, SearchType},
+        },
+        url::{SERVER_AGENTS, SERVER_LIBRARY_SECTIONS},
+        Error, Server,
+    };
+
+    #[plex_api_test_helper::offline_test]
+    async fn create_library(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let m_agents = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path(SERVER_AGENTS)
+                .query_param("mediaType", "1");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/system/agents.json");
+        });
+        let m_scanners = mock_server.mock(|when, then| {
+            when.method(GET).path("/system/scanners/1");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/system/scanners.json");
+        });
+
+        let m = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path(SERVER_LIBRARY_SECTIONS)
+                .query_param("name", "Movies")
+                .query_param("type", "movie")
+                .query_param("agent", "tv.plex.agents.movie")
+                .query_param("scanner", "Plex Movie")
+                .query_param("language", "en-US")
+                .query_param("location", "/data/movies")
+                .query_param("location", "/data/more-movies")
+                .query_param("prefs[enableCinemaTrailers]", "0");
+            then.status(200);
+        });
+
+        server
+            .create_library(&NewLibrary {
+                name: "Movies".to_owned(),
+                library_type: LibraryType::Movie,
+                agent: "tv.plex.agents.movie".to_owned(),
+                scanner: "Plex Movie".to_owned(),
+                language: "en-US".to_owned(),
+                locations: vec!["/data/movies".to_owned(), "/data/more-movies".to_owned()],
+                preferences: vec![("enableCinemaTrailers".to_owned(), "0".to_owned())],
+            })
+            .await
+            .unwrap();
+        m_agents.assert();
+        m_scanners.assert();
+        m.assert();
+
+        let library = NewLibrary {
+            name: "Movies".to_owned(),
+            library_type: LibraryType::Movie,
+            agent: "tv.plex.agents.series".to_owned(),
+            scanner: "Plex Movie".to_owned(),
+            language: "en-US".to_owned(),
+            locations: vec!["/data/movies".to_owned()],
+            preferences: Vec::new(),
+        };
+        let result = server.create_library(&library).await;
+        assert!(
+            matches!(result, Err(Error::LibraryAgentNotSupported(ref agent)) if agent == "tv.plex.agents.series"),
+            "unexpected result: {result:?}"
+        );
+
+        let result = server
+            .create_library(&NewLibrary {
+                agent: "tv.plex.agents.movie".to_owned(),
+                scanner: "Plex Series Scanner".to_owned(),
+                ..library
+            })
+            .await;
+        assert!(
+            matches!(result, Err(Error::LibraryScannerNotSupported(ref scanner)) if scanner == "Plex Series Scanner"),
+            "unexpected result: {result:?}"
+        );
+        m.assert_calls(1);
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn create_library_without_locations(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let m = mock_server.mock(|when, then| {
+            when.any_request();
+            then.status(200);
+        });
+
+        let result = server
+            .create_library(&NewLibrary {
+                name: "Movies".to_owned(),
+                library_type: LibraryType::Movie,
+                agent: "tv.plex.agents.movie".to_owned(),
+                scanner: "Plex Movie".to_owned(),
+                language: "en-US".to_owned(),
+                locations: Vec::new(),
+                preferences: Vec::new(),
+            })
+            .await;
+        assert!(
+            matches!(result, Err(Error::NoItemsProvided)),
+            "unexpected result: {result:?}"
+        );
+        m.assert_calls(0);
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn agents_and_scanners(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path(SERVER_AGENTS)
+                .query_param("mediaType", "1");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/system/agents.json");
+        });
+
+        let agents = server.agents(Some(SearchType::Movie)).await.unwrap();
+        m.assert();
+        assert_eq!(
+            agents
+                .iter()
+                .map(|a| a.identifier.as_str())
+                .collect::<Vec<_>>(),
+            vec!["tv.plex.agents.movie", "tv.plex.agents.none"]
+        );
+        assert!(agents[0].has_prefs);
+        assert_eq!(agents[0].media_types[0].media_type, SearchType::Movie);
+        assert_eq!(
+            agents[0].media_types[0]
+                .languages
+                .iter()
+                .map(|l| l.code.as_str())
+                .collect::<Vec<_>>(),
+            vec!["en-US", "de-DE"]
+        );
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET).path("/system/scanners/1");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/system/scanners.json");
+        });
+
+        let scanners = server.scanners(SearchType::Movie).await.unwrap();
+        m.assert();
+        assert_eq!(
+            scanners.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+            vec!["Plex Movie", "Plex Video Files Scanner"]
+        );
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn library_locations(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let library = server.libraries().remove(0);
+
+        let _m_sections = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_LIBRARY_SECTIONS);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/library/sections.json");
+        });
+
+        let sections = server.library_sections().await.unwrap();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[1].title, "TV Shows");
+        assert_eq!(sections[1].hidden, Some(false));
+
+        let locations = library.locations().await.unwrap();
+        assert_eq!(
+            locations
+                .iter()
+                .map(|l| l.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/data/movies", "/data/more-movies"]
+        );
+        assert_eq!(locations[1].id, Some(7));
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/library/sections/1")
+                .query_param("agent", "com.plexapp.agents.imdb")
+                .query_param("location", "/data/movies")
+                .query_param("location", "/data/more-movies")
+                .query_param("location", "/data/new-movies");
+            then.status(200);
+        });
+
+        library.add_location("/data/new-movies").await.unwrap();
+        m.assert();
+
+        // Already part of the library, so nothing is changed.
+        library.add_location("/data/movies").await.unwrap();
+        m.assert_calls(1);
+        m.delete();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/library/sections/1")
+                .query_param("agent", "com.plexapp.agents.imdb")
+                .query_param("location", "/data/movies")
+                .query_param_not("location", "/data/more-movies");
+            then.status(200);
+        });
+
+        library.remove_location("/data/more-movies").await.unwrap();
+        m.assert();
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn remove_last_location(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let library = server
+            .libraries()
+            .into_iter()
+            .find(|library| library.id() == "2")
+            .unwrap();
+
+        let _m_sections = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_LIBRARY_SECTIONS);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/library/sections.json");
+        });
+        let m = mock_server.mock(|when, then| {
+            when.method(PUT).path("/library/sections/2");
+            then.status(200);
+        });
+
+        let result = library.remove_location("/data/tv").await;
+        assert!(
+            matches!(result, Err(Error::NoItemsProvided)),
+            "unexpected result: {result:?}"
+        );
+        m.assert_calls(0);
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn library_preferences(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let library = server.libraries().remove(0);
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/sections/1/prefs");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/library/prefs.json");
+        });
+
+        let mut prefs = library.preferences().await.unwrap();
+        m.assert();
+        assert!(matches!(
+            prefs.get("enableCinemaTrailers").unwrap().value,
+            Value::Bool(true)
+        ));
+
+        prefs.set("hidden", Value::Int(1)).unwrap();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/library/sections/1/prefs")
+                .query_param("hidden", "1");
+            then.status(200);
+        });
+
+        prefs.commit().await.unwrap();
+        m.assert();
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn delete_library(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let library = server.libraries().remove(0);
+
+        let m = mock_server.mock(|when, then| {
+            when.method(DELETE).path("/library/sections/1");
+            then.status(200);
+        });
+
+        library.delete().await.unwrap();
+        m.assert();
+    }
+}
+
+mod online {
+    use super::fixtures::online::server::*;
+    use plex_api::{media_container::server::library::SearchType, Server};
+
+    #[plex_api_test_helper::online_test]
+    async fn agents_and_scanners(#[future] server: Server) {
+        let agents = server.agents(Some(SearchType::Movie)).await.unwrap();
+        assert!(!agents.is_empty());
+
+        let scanners = server.scanners(SearchType::Movie).await.unwrap();
+        assert!(!scanners.is_empty());
+    }
+
+    #[plex_api_test_helper::online_test]
+    async fn library_locations(#[future] server: Server) {
+        let sections = server.library_sections().await.unwrap();
+        assert_eq!(sections.len(), server.libraries().len());
+
+        for library in server.libraries() {
+            assert!(!library.locations().await.unwrap().is_empty());
+        }
+    }
+}
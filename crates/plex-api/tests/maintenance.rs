@@ -0,0 +1,170 @@
+mod fixtures;
+
+mod offline {
+    use std::time::Duration;
+
+    use super::fixtures::offline::{server::*, Mocked};
+    use futures::future;
+    use futures_timer::Delay;
+    use httpmock::Method::{GET, PUT};
+    use plex_api::{
+        url::{SERVER_ACTIVITIES, SERVER_OPTIMIZE_DATABASE},
+        Error, Server,
+    };
+
+    #[plex_api_test_helper::offline_test]
+    async fn scan_library(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let library = &server.libraries()[0];
+
+        let mut m_activities = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_ACTIVITIES);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/activities/idle.json");
+        });
+
+        let m_scan = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/library/sections/1/refresh")
+                .query_param("path", "/data/movies/Sintel (2010)");
+            then.status(200);
+        });
+
+        let mut handle = library
+            .scan(Some("/data/movies/Sintel (2010)"))
+            .await
+            .unwrap();
+        m_activities.assert();
+        m_scan.assert();
+        m_activities.delete();
+
+        let mut m_activities = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_ACTIVITIES);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/activities/scanning.json");
+        });
+
+        // The activity that was already running isn't the scan.
+        let activity = handle.activity().await.unwrap().unwrap();
+        assert_eq!(activity.uuid, "a0f6b8e2-5f0a-4d62-8a5e-0c3b6f0f7d21");
+        assert_eq!(activity.activity_type, "library.update.section");
+        assert_eq!(activity.progress, 42);
+
+        let finish = async {
+            Delay::new(Duration::from_millis(100)).await;
+            m_activities.delete();
+            mock_server.mock(|when, then| {
+                when.method(GET).path(SERVER_ACTIVITIES);
+                then.status(200)
+                    .header("content-type", "text/json")
+                    .body_from_file("tests/mocks/server/activities/idle.json");
+            })
+        };
+
+        let (result, m_activities) =
+            future::join(handle.wait(Duration::from_millis(20)), finish).await;
+        result.unwrap();
+        m_activities.assert_calls(1);
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn library_operations(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let library = &server.libraries()[0];
+
+        let _m_activities = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_ACTIVITIES);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/activities/empty.json");
+        });
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/library/sections/1/refresh")
+                .query_param("force", "1");
+            then.status(200);
+        });
+        library.refresh_metadata().await.unwrap();
+        m.assert();
+        m.delete();
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(PUT).path("/library/sections/1/analyze");
+            then.status(200);
+        });
+        library.analyze().await.unwrap();
+        m.assert();
+        m.delete();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(PUT).path("/library/sections/1/emptyTrash");
+            then.status(401)
+                .header("content-type", "text/html")
+                .body("<html><head><title>Unauthorized</title></head><body><h1>401 Unauthorized</h1></body></html>");
+        });
+        let result = library.empty_trash().await;
+        m.assert();
+        assert!(
+            matches!(
+                result,
+                Err(Error::UnexpectedApiResponse {
+                    status_code: 401,
+                    ..
+                })
+            ),
+            "unexpected result: {result:?}"
+        );
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn optimize_database(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let mut m_activities = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_ACTIVITIES);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/activities/empty.json");
+        });
+
+        let m = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path(SERVER_OPTIMIZE_DATABASE)
+                .query_param("async", "1");
+            then.status(200)
+                .header("X-Plex-Activity", "d7e3c4b1-92f8-4c1e-b0a4-6e5f2a9d3c18");
+        });
+
+        let handle = server.optimize_database().await.unwrap();
+        m.assert();
+        m_activities.delete();
+
+        let _m_activities = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_ACTIVITIES);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/activities/optimizing.json");
+        });
+
+        let activity = handle.activity().await.unwrap().unwrap();
+        assert_eq!(activity.activity_type, "database.optimize");
+        assert_eq!(activity.progress, -1);
+    }
+}
+
+mod online {
+    use std::time::Duration;
+
+    use super::fixtures::online::server::*;
+    use plex_api::Server;
+
+    #[plex_api_test_helper::online_test]
+    async fn scan_library(#[future] server: Server) {
+        let libraries = server.libraries();
+        let mut handle = libraries[0].scan(None).await.unwrap();
+        handle.wait(Duration::from_millis(500)).await.unwrap();
+    }
+}
@@ -0,0 +1,173 @@
+mod fixtures;
+
+mod offline {
+    use super::fixtures::offline::{server::*, Mocked};
+    use httpmock::{
+        Method::{GET, PUT},
+        MockServer,
+    };
+    use plex_api::{
+        library::{Item, MetadataItemWithMatching, Movie},
+        media_container::server::library::Guid,
+        url::SERVER_MEDIA_PROVIDERS,
+        Error, Server,
+    };
+
+    async fn movie(server: &Server, mock_server: &MockServer) -> Movie {
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/metadata/108");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/metadata_108.json");
+        });
+
+        let item = server.item_by_id("108").await.unwrap();
+        m.assert();
+        m.delete();
+
+        match item {
+            Item::Movie(movie) => movie,
+            _ => panic!("Unexpected item type"),
+        }
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn search_and_apply_match(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let movie = movie(&server, &mock_server).await;
+        let feature = server.match_feature().unwrap();
+
+        let m_matches = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/library/metadata/108/matches")
+                .query_param("manual", "1")
+                .query_param("title", "Interstate 60")
+                .query_param("year", "2002")
+                .query_param("agent", "tv.plex.agents.movie");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/matching/matches_108.json");
+        });
+
+        let candidates = movie
+            .search_matches(
+                &feature,
+                Some("Interstate 60"),
+                Some(2002),
+                Some("tv.plex.agents.movie"),
+            )
+            .await
+            .unwrap();
+        m_matches.assert();
+
+        assert_eq!(candidates.len(), 3);
+        assert!(candidates[0].is_matched());
+        assert_eq!(candidates[1].score(), 85);
+        assert_eq!(candidates[1].year(), Some(2003));
+        assert_eq!(
+            candidates[1].guid(),
+            Some(&Guid::Plex(
+                "movie".to_owned(),
+                "5d776b59ad5437001f79c6f8".to_owned()
+            ))
+        );
+        assert_eq!(
+            candidates[2].guid(),
+            Some(&Guid::Imdb("tt0165832?lang=en".to_owned()))
+        );
+
+        let m_match = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/library/metadata/108/match")
+                .query_param("guid", "plex://movie/5d776b59ad5437001f79c6f8")
+                .query_param("name", "Interstate 60: Episodes of the Road")
+                .query_param("year", "2003");
+            then.status(200);
+        });
+
+        movie.apply_match(&feature, &candidates[1]).await.unwrap();
+        m_match.assert();
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn search_matches_defaults(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let movie = movie(&server, &mock_server).await;
+        let feature = server.match_feature().unwrap();
+
+        let m_matches = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/library/metadata/108/matches")
+                .query_param("manual", "1")
+                .query_param_missing("title")
+                .query_param_missing("year")
+                .query_param_missing("agent");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/matching/matches_108.json");
+        });
+
+        let candidates = movie
+            .search_matches(&feature, None, None, None)
+            .await
+            .unwrap();
+        m_matches.assert();
+        assert_eq!(candidates.len(), 3);
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn unmatch_and_refresh(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let movie = movie(&server, &mock_server).await;
+        let feature = server.match_feature().unwrap();
+
+        let m_unmatch = mock_server.mock(|when, then| {
+            when.method(PUT).path("/library/metadata/108/unmatch");
+            then.status(200);
+        });
+        let m_refresh = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/library/metadata/108/refresh")
+                .query_param("force", "1");
+            then.status(200);
+        });
+
+        movie.unmatch(&feature).await.unwrap();
+        m_unmatch.assert();
+
+        movie.refresh_metadata().await.unwrap();
+        m_refresh.assert();
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn match_not_supported(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let mut providers: serde_json::Value =
+            serde_json::from_str(include_str!("mocks/server/media/providers_unclaimed.json"))
+                .unwrap();
+        for provider in providers["MediaContainer"]["MediaProvider"]
+            .as_array_mut()
+            .unwrap()
+        {
+            provider["Feature"]
+                .as_array_mut()
+                .unwrap()
+                .retain(|feature| feature["type"] != "match");
+        }
+        let m_providers = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_MEDIA_PROVIDERS);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body(providers.to_string());
+        });
+
+        let server = server.refresh().await.unwrap();
+        m_providers.assert();
+        let result = server.match_feature();
+        assert!(
+            matches!(result, Err(Error::MatchNotSupported)),
+            "unexpected result: {result:?}"
+        );
+    }
+}
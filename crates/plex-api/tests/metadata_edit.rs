@@ -0,0 +1,125 @@
+mod fixtures;
+
+mod offline {
+    use super::fixtures::offline::{server::*, Mocked};
+    use httpmock::{
+        Method::{GET, PUT},
+        MockServer,
+    };
+    use plex_api::{
+        library::{Item, MetadataItem, Movie, TagField},
+        Server,
+    };
+    use time::{Date, Month};
+
+    async fn movie(server: &Server, mock_server: &MockServer) -> Movie {
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/metadata/108");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/metadata_108.json");
+        });
+
+        let item = server.item_by_id("108").await.unwrap();
+        m.assert();
+        m.delete();
+
+        match item {
+            Item::Movie(movie) => movie,
+            _ => panic!("Unexpected item type"),
+        }
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn edit_fields(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let movie = movie(&server, &mock_server).await;
+        assert!(!movie.is_locked("title"));
+
+        let m_edit = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/library/metadata/108")
+                .query_param("title.value", "Interstate Sixty")
+                .query_param("title.locked", "1")
+                .query_param("titleSort.value", "Interstate 60")
+                .query_param("titleSort.locked", "1")
+                .query_param("contentRating.value", "PG-13")
+                .query_param("contentRating.locked", "1")
+                .query_param("originallyAvailableAt.value", "2002-04-20")
+                .query_param("originallyAvailableAt.locked", "1")
+                .query_param("summary.locked", "0");
+            then.status(200);
+        });
+        let m_refresh = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/metadata/108");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/metadata_108_edited.json");
+        });
+
+        let movie: Movie = movie
+            .edit()
+            .title("Interstate Sixty")
+            .sort_title("Interstate 60")
+            .content_rating("PG-13")
+            .originally_available_at(Date::from_calendar_date(2002, Month::April, 20).unwrap())
+            .unlock("summary")
+            .apply()
+            .await
+            .unwrap();
+        m_edit.assert();
+        m_refresh.assert();
+
+        assert_eq!(movie.title(), "Interstate Sixty");
+        assert!(movie.is_locked("title"));
+        assert!(!movie.is_locked("summary"));
+        assert_eq!(
+            movie.metadata().originally_available_at.unwrap().month(),
+            Month::April
+        );
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn edit_tags(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let movie = movie(&server, &mock_server).await;
+
+        let m_edit = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/library/metadata/108")
+                .query_param("genre[0].tag.tag", "Road Movie")
+                .query_param("genre[].tag.tag-", "Drama")
+                .query_param("genre.locked", "1")
+                .query_param("label[0].tag.tag", "Spreadsheet")
+                .query_param("label.locked", "0");
+            then.status(200);
+        });
+        let m_refresh = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/metadata/108");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/metadata_108_edited.json");
+        });
+
+        let movie: Movie = movie
+            .edit()
+            .add_tags(TagField::Genre, &["Road Movie"])
+            .remove_tags(TagField::Genre, &["Drama"])
+            .add_tags(TagField::Label, &["Spreadsheet"])
+            .unlock(TagField::Label.name())
+            .apply()
+            .await
+            .unwrap();
+        m_edit.assert();
+        m_refresh.assert();
+
+        let genres: Vec<&str> = movie
+            .metadata()
+            .genres
+            .iter()
+            .map(|genre| genre.tag.as_str())
+            .collect();
+        assert!(genres.contains(&"Road Movie"));
+        assert!(!genres.contains(&"Drama"));
+    }
+}
@@ -2,13 +2,88 @@ mod fixtures;
 
 mod offline {
     use super::fixtures::offline::{myplex::*, Mocked};
-    use httpmock::Method::GET;
+    use futures_timer::Delay;
+    use httpmock::{
+        Method::{DELETE, GET, PUT},
+        MockServer,
+    };
     use plex_api::{
-        device::DeviceConnection,
+        device::{ConnectionStrategy, DeviceCleanup, DeviceConnection},
         url::{MYPLEX_DEVICES, MYPLEX_RESOURCES, SERVER_MEDIA_PROVIDERS},
-        MyPlex,
+        Error, MyPlex, Server,
+    };
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+        time::Duration,
     };
 
+    /// Describes a server with the given connections, each is a tuple of
+    /// uri, local and relay.
+    fn resources(
+        identifier: &str,
+        https_required: bool,
+        connections: &[(&str, bool, bool)],
+    ) -> String {
+        let connections = connections
+            .iter()
+            .map(|(uri, local, relay)| {
+                let uri = uri.parse::<http::Uri>().unwrap();
+                serde_json::json!({
+                    "protocol": uri.scheme_str(),
+                    "address": uri.host(),
+                    "port": uri.port_u16(),
+                    "uri": uri.to_string(),
+                    "local": local,
+                    "relay": relay,
+                    "IPv6": false,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut resources: serde_json::Value =
+            serde_json::from_str(include_str!("mocks/myplex/api/v2/resources.json")).unwrap();
+        resources[0]["clientIdentifier"] = identifier.into();
+        resources[0]["httpsRequired"] = https_required.into();
+        resources[0]["connections"] = connections.into();
+        resources.to_string()
+    }
+
+    async fn connect_server(
+        myplex: &MyPlex,
+        mock_server: &MockServer,
+        body: String,
+        strategy: &ConnectionStrategy,
+    ) -> plex_api::Result<Server> {
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET).path(MYPLEX_RESOURCES);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(body);
+        });
+
+        let device_manager = myplex.device_manager().unwrap();
+        let resources = device_manager.resources().await.unwrap();
+        m.assert();
+        m.delete();
+
+        match resources[0].connect_with(strategy).await? {
+            DeviceConnection::Server(server) => Ok(*server),
+            DeviceConnection::Player(_) => panic!("Connected to a strange device"),
+        }
+    }
+
+    fn mock_providers(mock_server: &MockServer) -> httpmock::Mock<'_> {
+        mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_MEDIA_PROVIDERS);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body_from_file("tests/mocks/server/media/providers_free.json");
+        })
+    }
+
     #[plex_api_test_helper::offline_test]
     async fn load_devices(#[future] myplex: Mocked<MyPlex>) {
         let (myplex, mock_server) = myplex.split();
@@ -31,34 +106,244 @@ mod offline {
             .unwrap();
     }
 
+    /// Describes devices, each is a tuple of id, client identifier, product
+    /// and the last seen timestamp.
+    fn devices(devices: &[(u32, &str, &str, i64)]) -> String {
+        let devices = devices
+            .iter()
+            .map(|(id, identifier, product, last_seen_at)| {
+                format!(
+                    r#"<Device name="Device {id}" publicAddress="1.0.0.1" product="{product}" productVersion="1.0" platform="Linux" platformVersion="5.4" device="Linux" provides="" clientIdentifier="{identifier}" id="{id}" token="auth_token" createdAt="1628211599" lastSeenAt="{last_seen_at}"/>"#
+                )
+            })
+            .collect::<String>();
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<MediaContainer publicAddress="1.0.0.1" size="{}">{devices}</MediaContainer>"#,
+            devices.len()
+        )
+    }
+
     #[plex_api_test_helper::offline_test]
-    async fn load_resources(#[future] myplex: Mocked<MyPlex>) {
+    async fn device_sync_list(#[future] myplex: Mocked<MyPlex>) {
         let (myplex, mock_server) = myplex.split();
 
-        let resources_mock = mock_server.mock(|when, then| {
+        let devices_mock = mock_server.mock(|when, then| {
+            when.method(GET).path(MYPLEX_DEVICES);
+            then.status(200)
+                .header("content-type", "application/xml")
+                .body_from_file("tests/mocks/myplex/devices.xml");
+        });
+        let sync_items_mock = mock_server.mock(|when, then| {
+            when.method(GET).path("/devices/9/sync_items");
+            then.status(200)
+                .header("content-type", "application/xml")
+                .body_from_file("tests/mocks/myplex/devices/sync_items.xml");
+        });
+        let remove_mocks = [1001, 1002].map(|id| {
+            mock_server.mock(|when, then| {
+                when.method(DELETE)
+                    .path(format!("/devices/9/sync_items/{id}"));
+                then.status(200);
+            })
+        });
+
+        let device_manager = myplex.device_manager().unwrap();
+        let devices = device_manager.devices().await.unwrap();
+        devices_mock.assert();
+
+        let device = devices.iter().find(|d| d.identifier() == "9").unwrap();
+        assert_eq!(device.sync_list().unwrap().version, 2);
+
+        let items = device.sync_items().await.unwrap();
+        sync_items_mock.assert();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].root_title, "The Expanse");
+        assert_eq!(items[0].status.as_ref().unwrap().state, "complete");
+
+        device.clear_sync_list().await.unwrap();
+        sync_items_mock.assert_calls(2);
+        for mock in remove_mocks {
+            mock.assert();
+        }
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn device_rename_and_remove(#[future] myplex: Mocked<MyPlex>) {
+        let (myplex, mock_server) = myplex.split();
+        let current = myplex.client().x_plex_client_identifier.clone();
+
+        let _devices_mock = mock_server.mock(|when, then| {
+            when.method(GET).path(MYPLEX_DEVICES);
+            then.status(200)
+                .header("content-type", "application/xml")
+                .body(devices(&[
+                    (1, "stale", "Plex Web", 1628211599),
+                    (2, &current, "Plex Web", 1628211599),
+                ]));
+        });
+        let rename_mock = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/devices/1.xml")
+                .query_param("name", "Old browser");
+            then.status(200);
+        });
+        let remove_mocks = [1, 2].map(|id| {
+            mock_server.mock(|when, then| {
+                when.method(DELETE).path(format!("/devices/{id}.xml"));
+                then.status(200);
+            })
+        });
+
+        let device_manager = myplex.device_manager().unwrap();
+        let mut devices = device_manager.devices().await.unwrap();
+        let current_device = devices.pop().unwrap();
+        let mut stale_device = devices.pop().unwrap();
+        assert!(current_device.is_current());
+        assert!(!stale_device.is_current());
+
+        stale_device.rename("Old browser").await.unwrap();
+        rename_mock.assert();
+        assert_eq!(stale_device.name(), "Old browser");
+
+        stale_device.remove().await.unwrap();
+        remove_mocks[0].assert();
+
+        let error = current_device.clone().remove().await.unwrap_err();
+        assert!(matches!(error, Error::CurrentDeviceRemovalRefused));
+        remove_mocks[1].assert_calls(0);
+
+        current_device.force_remove().await.unwrap();
+        remove_mocks[1].assert();
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn device_without_id(#[future] myplex: Mocked<MyPlex>) {
+        let (myplex, mock_server) = myplex.split();
+
+        let _resources_mock = mock_server.mock(|when, then| {
             when.method(GET).path(MYPLEX_RESOURCES);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body_from_file("tests/mocks/myplex/api/v2/resources.json");
+        });
+
+        let device_manager = myplex.device_manager().unwrap();
+        let device = device_manager.resources().await.unwrap().remove(0);
+        let error = device.remove().await.unwrap_err();
+        assert!(matches!(error, Error::DeviceIdNotAvailable));
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn devices_cleanup(#[future] myplex: Mocked<MyPlex>) {
+        let (myplex, mock_server) = myplex.split();
+        let current = myplex.client().x_plex_client_identifier.clone();
+
+        let _devices_mock = mock_server.mock(|when, then| {
+            when.method(GET).path(MYPLEX_DEVICES);
             then.status(200)
                 .header("content-type", "application/xml")
-                .body_from_file("tests/mocks/myplex/api/resources.xml");
+                .body(devices(&[
+                    (1, "stale", "Plex Web", 1600000000),
+                    (2, "recent", "Plex Web", 1700000000),
+                    (3, "other", "Plex for iOS", 1600000000),
+                    (4, &current, "Plex Web", 1600000000),
+                ]));
+        });
+        let mut remove_mocks = [1, 2, 3, 4].map(|id| {
+            mock_server.mock(|when, then| {
+                when.method(DELETE).path(format!("/devices/{id}.xml"));
+                then.status(200);
+            })
+        });
+
+        let device_manager = myplex.device_manager().unwrap();
+        let mut cleanup = DeviceCleanup {
+            last_seen_before: Some(time::OffsetDateTime::from_unix_timestamp(1650000000).unwrap()),
+            products: vec!["Plex Web".to_owned()],
+            ..Default::default()
+        };
+
+        let removed = device_manager.cleanup(&cleanup).await.unwrap();
+        assert_eq!(removed, vec!["stale".to_owned()]);
+        remove_mocks[0].assert();
+        remove_mocks[1].assert_calls(0);
+        remove_mocks[2].assert_calls(0);
+        remove_mocks[3].assert_calls(0);
+
+        cleanup.force = true;
+        let removed = device_manager.cleanup(&cleanup).await.unwrap();
+        assert_eq!(removed, vec!["stale".to_owned(), current.clone()]);
+        remove_mocks[3].assert();
+
+        // Nothing is removed without any conditions.
+        let result = device_manager.cleanup(&DeviceCleanup::default()).await;
+        assert!(
+            matches!(result, Err(Error::DeviceCleanupWithoutConditions)),
+            "unexpected result: {result:?}"
+        );
+
+        // A device that can't be removed doesn't stop the others.
+        remove_mocks[0].delete();
+        let mut failing_mock = mock_server.mock(|when, then| {
+            when.method(DELETE).path("/devices/1.xml");
+            then.status(500);
+        });
+        let result = device_manager.cleanup(&cleanup).await;
+        match result {
+            Err(Error::DeviceCleanupIncomplete { removed, failed }) => {
+                assert_eq!(removed, vec![current]);
+                assert_eq!(failed.len(), 1);
+                assert_eq!(failed[0].0, "stale");
+            }
+            result => panic!("unexpected result: {result:?}"),
+        }
+        failing_mock.assert();
+        failing_mock.delete();
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn load_resources(#[future] myplex: Mocked<MyPlex>) {
+        let (myplex, mock_server) = myplex.split();
+
+        let resources_mock = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path(MYPLEX_RESOURCES)
+                .query_param("includeHttps", "1")
+                .query_param("includeRelay", "1")
+                .query_param("includeIPv6", "1");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body_from_file("tests/mocks/myplex/api/v2/resources.json");
         });
 
         let device_manager = myplex.device_manager().unwrap();
 
         let resources = device_manager.resources().await;
         resources_mock.assert();
-        resources.unwrap();
+        let resources = resources.unwrap();
+        let device = &resources[0];
+        assert_eq!(device.identifier(), "client_id2");
+        assert!(device.is_server());
+        assert!(device.is_owned());
+        assert!(device.is_online());
+        assert_eq!(device.access_token(), Some("auth_token"));
+        assert_eq!(device.connections().len(), 4);
+        assert!(device.connections()[0].local);
+        assert!(device.connections()[3].relay);
     }
 
     #[plex_api_test_helper::offline_test]
     async fn connection_from_device(#[future] myplex: Mocked<MyPlex>) {
         let (myplex, mock_server) = myplex.split();
-        let body = include_str!("mocks/myplex/api/resources.xml")
-            .replace("http://1.0.0.2:443", &mock_server.base_url());
+        let body = include_str!("mocks/myplex/api/v2/resources.json")
+            .replace("https://example.org:443", &mock_server.base_url());
 
         let mut resources_mock = mock_server.mock(|when, then| {
             when.method(GET).path(MYPLEX_RESOURCES);
             then.status(200)
-                .header("content-type", "application/xml")
+                .header("content-type", "application/json")
                 .body(body);
         });
 
@@ -88,6 +373,162 @@ mod offline {
             panic!("No devices found");
         }
     }
+
+    #[plex_api_test_helper::offline_test]
+    async fn connection_prefers_local(#[future] myplex: Mocked<MyPlex>) {
+        let (myplex, mock_server) = myplex.split();
+        let local_server = MockServer::start_async().await;
+        mock_providers(&mock_server);
+        mock_providers(&local_server);
+
+        let body = resources(
+            "prefers_local",
+            false,
+            &[
+                (&mock_server.base_url(), false, true),
+                (
+                    &mock_server.base_url().replace("127.0.0.1", "localhost"),
+                    false,
+                    false,
+                ),
+                (&local_server.base_url(), true, false),
+            ],
+        );
+        let server = connect_server(&myplex, &mock_server, body, &ConnectionStrategy::default())
+            .await
+            .unwrap();
+        assert_eq!(server.client().api_url, local_server.base_url().as_str());
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn connection_filters(#[future] myplex: Mocked<MyPlex>) {
+        let (myplex, mock_server) = myplex.split();
+        mock_providers(&mock_server);
+
+        // Insecure connections aren't used when the server requires https.
+        let body = resources(
+            "https_required",
+            true,
+            &[(&mock_server.base_url(), true, false)],
+        );
+        let result =
+            connect_server(&myplex, &mock_server, body, &ConnectionStrategy::default()).await;
+        assert!(
+            matches!(result, Err(Error::DeviceConnectionsIsEmpty)),
+            "unexpected result: {result:?}"
+        );
+
+        let body = resources(
+            "relay_only",
+            false,
+            &[(&mock_server.base_url(), false, true)],
+        );
+        let strategy = ConnectionStrategy {
+            allow_relay: false,
+            ..Default::default()
+        };
+        let result = connect_server(&myplex, &mock_server, body.clone(), &strategy).await;
+        assert!(
+            matches!(result, Err(Error::DeviceConnectionsIsEmpty)),
+            "unexpected result: {result:?}"
+        );
+
+        let server = connect_server(&myplex, &mock_server, body, &ConnectionStrategy::default())
+            .await
+            .unwrap();
+        assert_eq!(server.client().api_url, mock_server.base_url().as_str());
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn connection_failover(#[future] myplex: Mocked<MyPlex>) {
+        let (myplex, mock_server) = myplex.split();
+        let remote_server = MockServer::start_async().await;
+
+        // A local connection that goes away after answering a single
+        // request. It only answers once the remote connection was tried.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let local_url = format!("http://{}", listener.local_addr().unwrap());
+        let (answer, answered) = mpsc::channel();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(&stream);
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            answered.recv().unwrap();
+
+            let body =
+                std::fs::read_to_string("tests/mocks/server/media/providers_free.json").unwrap();
+            write!(
+                &stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        });
+
+        let mut m = remote_server.mock(|when, then| {
+            when.method(GET).path(SERVER_MEDIA_PROVIDERS);
+            then.status(500);
+        });
+        let body = resources(
+            "failover",
+            false,
+            &[
+                (&remote_server.base_url(), false, false),
+                (&local_url, true, false),
+            ],
+        );
+        let remote_tried = async {
+            for _ in 0..250 {
+                if m.calls_async().await > 0 {
+                    answer.send(()).unwrap();
+                    return;
+                }
+                Delay::new(Duration::from_millis(20)).await;
+            }
+            panic!("The remote connection wasn't tried");
+        };
+        let strategy = ConnectionStrategy::default();
+        let (server, ()) = futures::join!(
+            connect_server(&myplex, &mock_server, body, &strategy),
+            remote_tried
+        );
+        let server = server.unwrap();
+        assert_eq!(server.client().api_url, local_url.as_str());
+        assert!(server.latency().is_some());
+        m.assert_calls_async(1).await;
+        m.delete();
+
+        // Requests switch to the remote connection once the local one is gone.
+        let m = mock_providers(&remote_server);
+        let server = server.refresh().await.unwrap();
+        m.assert_calls_async(1).await;
+        assert_eq!(server.client().api_url, remote_server.base_url().as_str());
+        assert_eq!(
+            server.client().base_url(),
+            remote_server.base_url().as_str()
+        );
+
+        // The remote connection worked last, but a local one is still
+        // preferred.
+        let local_server = MockServer::start_async().await;
+        let local_mock = mock_providers(&local_server);
+        let body = resources(
+            "failover",
+            false,
+            &[
+                (&remote_server.base_url(), false, false),
+                (&local_server.base_url(), true, false),
+            ],
+        );
+        let server = connect_server(&myplex, &mock_server, body, &ConnectionStrategy::default())
+            .await
+            .unwrap();
+        assert_eq!(server.client().api_url, local_server.base_url().as_str());
+        local_mock.assert_calls_async(1).await;
+    }
 }
 
 mod online {
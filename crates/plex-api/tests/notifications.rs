@@ -0,0 +1,87 @@
+mod fixtures;
+
+mod offline {
+    use super::fixtures::offline::{server::*, Mocked};
+    use futures::StreamExt;
+    use httpmock::Method::GET;
+    use plex_api::{
+        media_container::server::{
+            notification::{ActivityEvent, Notification},
+            session::PlaybackState,
+        },
+        url::SERVER_NOTIFICATIONS,
+        Server,
+    };
+
+    #[plex_api_test_helper::offline_test]
+    async fn notifications(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_NOTIFICATIONS);
+            then.status(200)
+                .header("content-type", "text/event-stream")
+                .body_from_file("tests/mocks/server/notifications.txt");
+        });
+
+        let mut notifications = server.notifications();
+
+        match notifications.next().await.unwrap().unwrap() {
+            Notification::Playing(playing) => {
+                assert_eq!(playing.rating_key, "57");
+                assert_eq!(playing.state, PlaybackState::Paused);
+                assert_eq!(playing.view_offset, 253000);
+            }
+            notification => panic!("Unexpected notification: {notification:?}"),
+        }
+
+        match notifications.next().await.unwrap().unwrap() {
+            Notification::Activity(activity) => {
+                assert_eq!(activity.event, ActivityEvent::Updated);
+                assert_eq!(activity.activity.progress, 50);
+                assert_eq!(
+                    activity
+                        .activity
+                        .context
+                        .unwrap()
+                        .library_section_id
+                        .as_deref(),
+                    Some("1")
+                );
+            }
+            notification => panic!("Unexpected notification: {notification:?}"),
+        }
+
+        match notifications.next().await.unwrap().unwrap() {
+            Notification::Timeline(entry) => {
+                assert_eq!(entry.item_id.as_deref(), Some("57"));
+                assert_eq!(entry.state, 5);
+            }
+            notification => panic!("Unexpected notification: {notification:?}"),
+        }
+
+        match notifications.next().await.unwrap().unwrap() {
+            Notification::TranscodeSessionEnd(key) => {
+                assert_eq!(key, "/transcode/sessions/8dbd2kqfpyf3qnyk3znhwrsf");
+            }
+            notification => panic!("Unexpected notification: {notification:?}"),
+        }
+
+        match notifications.next().await.unwrap().unwrap() {
+            Notification::Unknown { event, data } => {
+                assert_eq!(event, "account.ready");
+                assert_eq!(data["AccountUpdateNotification"]["accountID"], 1);
+            }
+            notification => panic!("Unexpected notification: {notification:?}"),
+        }
+
+        m.assert();
+
+        // The stream reconnects once the server closes the connection.
+        assert!(matches!(
+            notifications.next().await.unwrap().unwrap(),
+            Notification::Playing(_)
+        ));
+        m.assert_calls(2);
+    }
+}
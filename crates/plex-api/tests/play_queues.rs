@@ -0,0 +1,316 @@
+mod fixtures;
+
+mod offline {
+    use super::fixtures::offline::{server::*, Mocked};
+    use httpmock::{
+        Method::{DELETE, GET, POST, PUT},
+        MockServer,
+    };
+    use plex_api::{
+        library::{Library, MetadataItem, Movie, MovieLibrary},
+        play_queue::{PlayQueue, PlayQueueOptions, QueuePosition},
+        url::SERVER_PLAY_QUEUES,
+        Error, Server,
+    };
+
+    fn movie_library(server: &Server) -> MovieLibrary {
+        match server.libraries().remove(0) {
+            Library::Movie(library) => library,
+            _ => panic!("Unexpected library type"),
+        }
+    }
+
+    async fn movies(server: &Server, mock_server: &MockServer) -> Vec<Movie> {
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/sections/1/all");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/movie_library.json");
+        });
+
+        let movies = movie_library(server).movies().await.unwrap();
+        m.assert();
+        m.delete();
+        movies
+    }
+
+    async fn create_play_queue(server: &Server, mock_server: &MockServer) -> PlayQueue {
+        let mut m = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path(SERVER_PLAY_QUEUES)
+                .query_param("type", "video")
+                .query_param(
+                    "uri",
+                    "server://machine_id3/com.plexapp.plugins.library/library/sections/1/all?type=1",
+                )
+                .query_param("shuffle", "0")
+                .query_param("repeat", "1")
+                .query_param("continuous", "0");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/play_queues/created.json");
+        });
+
+        let search = movie_library(server).search();
+        let play_queue = server
+            .create_play_queue_from_search(
+                &search,
+                PlayQueueOptions {
+                    repeat: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        m.assert();
+        m.delete();
+        play_queue
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn create(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let play_queue = create_play_queue(&server, &mock_server).await;
+        assert_eq!(play_queue.id(), 4211);
+        assert_eq!(play_queue.version(), 1);
+        assert_eq!(play_queue.total_count(), 3);
+        assert!(!play_queue.is_shuffled());
+        assert_eq!(
+            play_queue
+                .items()
+                .iter()
+                .map(|i| i.title())
+                .collect::<Vec<_>>(),
+            vec!["Big Buck Bunny", "Elephants Dream", "Sintel"]
+        );
+        assert_eq!(play_queue.selected_offset(), Some(0));
+        assert_eq!(play_queue.selected_item().unwrap().rating_key(), "55");
+
+        let movies = movies(&server, &mock_server).await;
+
+        let m = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path(SERVER_PLAY_QUEUES)
+                .query_param("type", "video")
+                .query_param(
+                    "uri",
+                    "server://machine_id3/com.plexapp.plugins.library/library/metadata/55",
+                )
+                .query_param("shuffle", "1")
+                .query_param("continuous", "1");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/play_queues/created.json");
+        });
+
+        server
+            .create_play_queue(
+                &movies[0],
+                PlayQueueOptions {
+                    shuffle: true,
+                    continuous: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        m.assert();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/playQueues/4211")
+                .query_param("own", "1");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/play_queues/created.json");
+        });
+
+        let play_queue = server.play_queue(4211).await.unwrap();
+        m.assert();
+        assert_eq!(play_queue.items().len(), 3);
+    }
+
+    /// Creates a play queue from the item described by the mock and checks
+    /// the uri it was created with.
+    async fn create_from_item(
+        server: &Server,
+        mock_server: &MockServer,
+        rating_key: &str,
+        mock: &str,
+        queue_type: &str,
+    ) {
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/library/metadata/{rating_key}"));
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file(format!("tests/mocks/server/media/{mock}"));
+        });
+        let item = server.item_by_id(rating_key).await.unwrap();
+        m.assert();
+        m.delete();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path(SERVER_PLAY_QUEUES)
+                .query_param("type", queue_type)
+                .query_param(
+                    "uri",
+                    format!(
+                        "server://machine_id3/com.plexapp.plugins.library/library/metadata/{rating_key}"
+                    ),
+                );
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/play_queues/created.json");
+        });
+
+        server
+            .create_play_queue(&item, PlayQueueOptions::default())
+            .await
+            .unwrap();
+        m.assert();
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn create_from_show(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        create_from_item(&server, &mock_server, "22", "tv_library.json", "video").await;
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn create_from_album(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        create_from_item(&server, &mock_server, "157", "music_albums.json", "audio").await;
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn edit(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let mut play_queue = create_play_queue(&server, &mock_server).await;
+        let movies = movies(&server, &mock_server).await;
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/playQueues/4211")
+                .query_param(
+                    "uri",
+                    "server://machine_id3/com.plexapp.plugins.library/library/metadata/108",
+                )
+                .query_param("next", "1");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/play_queues/added.json");
+        });
+        play_queue
+            .add(&movies[2], QueuePosition::Next)
+            .await
+            .unwrap();
+        m.assert();
+        m.delete();
+        assert_eq!(play_queue.version(), 2);
+        let items = play_queue.items();
+        assert_eq!(items[1].title(), "Interstate 60");
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/playQueues/4211")
+                .query_param("center", "2002")
+                .query_param("window", "10");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/play_queues/added.json");
+        });
+        play_queue.refresh(Some(&items[2]), Some(10)).await.unwrap();
+        m.assert();
+        m.delete();
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/playQueues/4211/items/2004/move")
+                .query_param("after", "2003");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/play_queues/added.json");
+        });
+        play_queue
+            .move_item(&items[1], Some(&items[3]))
+            .await
+            .unwrap();
+        m.assert();
+        m.delete();
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(DELETE).path("/playQueues/4211/items/2002");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/play_queues/added.json");
+        });
+        play_queue.remove_item(&items[2]).await.unwrap();
+        m.assert();
+        m.delete();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(DELETE).path("/playQueues/4211/items");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/play_queues/cleared.json");
+        });
+        play_queue.clear().await.unwrap();
+        m.assert();
+        assert_eq!(play_queue.version(), 3);
+        assert_eq!(play_queue.total_count(), 1);
+
+        // Only items retrieved from the play queue can be moved or removed.
+        let result = play_queue.remove_item(&movies[2].clone().into()).await;
+        assert!(
+            matches!(result, Err(Error::PlayQueueItemIdNotAvailable)),
+            "unexpected result: {result:?}"
+        );
+    }
+}
+
+mod online {
+    use super::fixtures::online::server::*;
+    use plex_api::{
+        library::{Library, MetadataItem},
+        play_queue::{PlayQueueOptions, QueuePosition},
+        Server,
+    };
+
+    #[plex_api_test_helper::online_test_non_shared_server]
+    async fn create_and_edit(#[future] server: Server) {
+        let library = server
+            .libraries()
+            .into_iter()
+            .find_map(|l| match l {
+                Library::Movie(library) => Some(library),
+                _ => None,
+            })
+            .expect("no movie library found");
+        let movies = library.movies().await.unwrap();
+
+        let mut play_queue = server
+            .create_play_queue(&movies[0], PlayQueueOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            play_queue.selected_item().unwrap().rating_key(),
+            movies[0].rating_key()
+        );
+
+        play_queue
+            .add(&movies[1], QueuePosition::Last)
+            .await
+            .unwrap();
+        let items = play_queue.items();
+        assert_eq!(items.len(), 2);
+
+        play_queue.remove_item(&items[1]).await.unwrap();
+        assert_eq!(play_queue.items().len(), 1);
+
+        let fetched = server.play_queue(play_queue.id()).await.unwrap();
+        assert_eq!(fetched.version(), play_queue.version());
+    }
+}
@@ -0,0 +1,216 @@
+mod fixtures;
+
+mod offline {
+    use super::fixtures::offline::{client::*, server::*, Mocked};
+    use futures::StreamExt;
+    use httpmock::Method::GET;
+    use plex_api::{
+        media_container::player::{
+            ControllableFeature, PlaybackState, ProtocolCapability, TimelineMediaContainer,
+        },
+        url::{CLIENT_RESOURCES, SERVER_SYSTEM_PROXY},
+        Error, HttpClient, NavigationCommand, PlaybackType, Player, Server,
+    };
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+    };
+
+    async fn player(client: HttpClient, mock_server: &httpmock::MockServer) -> Player {
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET).path(CLIENT_RESOURCES);
+            then.status(200)
+                .header("content-type", "text/xml")
+                .body_from_file("tests/mocks/client/resources.xml");
+        });
+
+        let player = Player::new(mock_server.base_url(), client).await.unwrap();
+        m.assert();
+        m.delete();
+        player
+    }
+
+    fn assert_timeline(timelines: &TimelineMediaContainer) {
+        assert_eq!(timelines.location.as_deref(), Some("fullScreenVideo"));
+        let music = timelines.timeline(PlaybackType::Music).unwrap();
+        assert_eq!(music.state, PlaybackState::Stopped);
+        let video = timelines.timeline(PlaybackType::Video).unwrap();
+        assert_eq!(video.state, PlaybackState::Playing);
+        assert_eq!(video.time, Some(60000));
+        assert_eq!(video.duration, Some(81030));
+        assert_eq!(video.rating_key.as_deref(), Some("182"));
+        assert_eq!(video.volume, Some(80));
+        assert_eq!(video.mute, Some(false));
+        assert!(video.controllable.contains(&ControllableFeature::SeekTo));
+        assert_eq!(video.play_queue_item_id, Some(2001));
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn commands(client_authenticated: Mocked<HttpClient>) {
+        let (client, mock_server) = client_authenticated.split();
+        let player = player(client, &mock_server).await;
+
+        assert_eq!(player.machine_identifier(), "machine_id");
+        assert!(player.supports(ProtocolCapability::PlayQueues));
+        assert!(!player.supports(ProtocolCapability::Navigation));
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/player/playback/pause")
+                .query_param("type", "video")
+                .query_param("commandID", "1");
+            then.status(200);
+        });
+        player.pause(PlaybackType::Video).await.unwrap();
+        m.assert();
+        m.delete();
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/player/playback/seekTo")
+                .query_param("type", "video")
+                .query_param("offset", "60000")
+                .query_param("commandID", "2");
+            then.status(200);
+        });
+        // Clones of the player share the command sequence.
+        player
+            .clone()
+            .seek_to(PlaybackType::Video, 60000)
+            .await
+            .unwrap();
+        m.assert();
+        m.delete();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/player/playback/setStreams")
+                .query_param("type", "video")
+                .query_param("audioStreamID", "12")
+                .query_param("subtitleStreamID", "0")
+                .query_param("commandID", "3");
+            then.status(200);
+        });
+        player
+            .set_streams(PlaybackType::Video, Some(12), Some(0))
+            .await
+            .unwrap();
+        m.assert();
+
+        let result = player.navigate(NavigationCommand::Home).await;
+        assert!(
+            matches!(
+                result,
+                Err(Error::PlayerCapabilityNotSupported(
+                    ProtocolCapability::Navigation
+                ))
+            ),
+            "unexpected result: {result:?}"
+        );
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn timeline(client_authenticated: Mocked<HttpClient>) {
+        let (client, mock_server) = client_authenticated.split();
+        let player = player(client, &mock_server).await;
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/player/timeline/poll")
+                .query_param("wait", "1")
+                .query_param("commandID", "1");
+            then.status(200)
+                .header("content-type", "text/xml")
+                .body_from_file("tests/mocks/client/timeline.xml");
+        });
+
+        let timelines = player.timeline().await.unwrap();
+        m.assert();
+        assert_timeline(&timelines);
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn timeline_subscription(client_authenticated: Mocked<HttpClient>) {
+        let (client, mock_server) = client_authenticated.split();
+        let player = player(client, &mock_server).await;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/player/timeline/subscribe")
+                .query_param("port", local_addr.port().to_string())
+                .query_param("protocol", "http");
+            then.status(200);
+        });
+
+        let mut subscription = player.subscribe(listener).await.unwrap();
+        m.assert();
+        m.delete();
+
+        // The player pushes its timelines to the subscriber.
+        let body = std::fs::read_to_string("tests/mocks/client/timeline.xml").unwrap();
+        let mut stream = TcpStream::connect(local_addr).unwrap();
+        write!(
+            stream,
+            "POST /:/timeline HTTP/1.1\r\nContent-Type: text/xml\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+
+        let timelines = subscription.next().await.unwrap().unwrap();
+        assert_timeline(&timelines);
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/player/timeline/subscribe")
+                .query_param("commandID", "2");
+            then.status(200);
+        });
+        subscription.renew().await.unwrap();
+        m.assert();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET).path("/player/timeline/unsubscribe");
+            then.status(200);
+        });
+        subscription.unsubscribe().await.unwrap();
+        m.assert();
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn commands_via_proxy(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path(SERVER_SYSTEM_PROXY)
+                .header("X-Plex-Url", "http://192.168.1.5:32500/resources");
+            then.status(200)
+                .header("content-type", "text/xml")
+                .body_from_file("tests/mocks/client/resources.xml");
+        });
+
+        let player = Player::via_proxy("http://192.168.1.5:32500", &server)
+            .await
+            .unwrap();
+        m.assert();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path(SERVER_SYSTEM_PROXY)
+                .header(
+                    "X-Plex-Url",
+                    "http://192.168.1.5:32500/player/playback/setParameters?type=music&volume=80&commandID=1",
+                )
+                .header("X-Plex-Target-Client-Identifier", "machine_id");
+            then.status(200);
+        });
+        player.set_volume(PlaybackType::Music, 80).await.unwrap();
+        m.assert();
+    }
+}
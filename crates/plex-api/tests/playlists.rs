@@ -0,0 +1,334 @@
+mod fixtures;
+
+mod offline {
+    use super::fixtures::offline::{server::*, Mocked};
+    use futures::TryStreamExt;
+    use httpmock::{
+        Method::{DELETE, GET, POST, PUT},
+        MockServer,
+    };
+    use plex_api::{
+        library::{Library, MetadataItem, Movie, MovieLibrary, PagingOptions, Playlist, Video},
+        url::SERVER_PLAYLISTS,
+        Error, Server,
+    };
+    use time::OffsetDateTime;
+
+    fn movie_library(server: &Server) -> MovieLibrary {
+        match server.libraries().remove(0) {
+            Library::Movie(library) => library,
+            _ => panic!("Unexpected library type"),
+        }
+    }
+
+    async fn movies(server: &Server, mock_server: &MockServer) -> Vec<Movie> {
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/sections/1/all");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/movie_library.json");
+        });
+
+        let movies = movie_library(server).movies().await.unwrap();
+        m.assert();
+        m.delete();
+        movies
+    }
+
+    async fn create_playlist(server: &Server, mock_server: &MockServer) -> Playlist<Video> {
+        let movies = movies(server, mock_server).await;
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path(SERVER_PLAYLISTS)
+                .query_param("type", "video")
+                .query_param("title", "Blender Movies")
+                .query_param("smart", "0")
+                .query_param(
+                    "uri",
+                    "server://machine_id3/com.plexapp.plugins.library/library/metadata/55,57",
+                );
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/playlists/created.json");
+        });
+
+        let blender: Vec<Movie> = movies
+            .into_iter()
+            .filter(|m| ["55", "57"].contains(&m.rating_key()))
+            .collect();
+        let playlist = server
+            .create_playlist("Blender Movies", &blender)
+            .await
+            .unwrap();
+        m.assert();
+        m.delete();
+        playlist
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn create(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let playlist = create_playlist(&server, &mock_server).await;
+        assert_eq!(playlist.rating_key(), "170");
+        assert_eq!(playlist.title(), "Blender Movies");
+        assert!(!playlist.is_smart());
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn create_smart(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path(SERVER_PLAYLISTS)
+                .query_param("type", "video")
+                .query_param("title", "Movies Since 2007")
+                .query_param("smart", "1")
+                .query_param(
+                    "uri",
+                    "server://machine_id3/com.plexapp.plugins.library/library/sections/1/all?type=1&addedAt%3E%3E=1167609599",
+                );
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/metadata_168.json");
+        });
+
+        let search = movie_library(&server)
+            .search()
+            .added_at(OffsetDateTime::from_unix_timestamp(1167609600).unwrap()..);
+        let playlist: Playlist<Video> = server
+            .create_smart_playlist("Movies Since 2007", &search)
+            .await
+            .unwrap();
+        m.assert();
+        assert_eq!(playlist.rating_key(), "168");
+        assert!(playlist.is_smart());
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn edit(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let playlist = create_playlist(&server, &mock_server).await;
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/playlists/170")
+                .query_param("title", "Open Movies");
+            then.status(200);
+        });
+        playlist.rename("Open Movies").await.unwrap();
+        m.assert();
+        m.delete();
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/playlists/170")
+                .query_param("summary", "Made with Blender");
+            then.status(200);
+        });
+        playlist.set_summary("Made with Blender").await.unwrap();
+        m.assert();
+        m.delete();
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/library/metadata/170/posters")
+                .query_param("url", "https://example.com/poster.jpg");
+            then.status(200);
+        });
+        playlist
+            .set_poster("https://example.com/poster.jpg")
+            .await
+            .unwrap();
+        m.assert();
+        m.delete();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(DELETE).path("/playlists/170");
+            then.status(200);
+        });
+        playlist.delete().await.unwrap();
+        m.assert();
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn items_stream(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let playlist = create_playlist(&server, &mock_server).await;
+
+        let m_page1 = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/playlists/170/items")
+                .header("X-Plex-Container-Start", "0")
+                .header("X-Plex-Container-Size", "1");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/playlists/items_page1.json");
+        });
+        let m_page2 = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/playlists/170/items")
+                .header("X-Plex-Container-Start", "1")
+                .header("X-Plex-Container-Size", "1");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/playlists/items_page2.json");
+        });
+
+        let stream = playlist
+            .children_stream(PagingOptions {
+                page_size: 1,
+                prefetch: 1,
+            })
+            .await
+            .unwrap();
+        assert_eq!(stream.total_size(), 2);
+        m_page1.assert();
+        m_page2.assert_calls(0);
+
+        let items: Vec<Video> = stream.try_collect().await.unwrap();
+        m_page2.assert();
+        assert_eq!(
+            items.iter().map(|i| i.title()).collect::<Vec<_>>(),
+            vec!["Big Buck Bunny", "Sintel"]
+        );
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn items(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let playlist = create_playlist(&server, &mock_server).await;
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET).path("/playlists/170/items");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/playlists/items.json");
+        });
+        let items = playlist.children().await.unwrap();
+        m.assert();
+        m.delete();
+        assert_eq!(
+            items.iter().map(|i| i.title()).collect::<Vec<_>>(),
+            vec!["Big Buck Bunny", "Sintel"]
+        );
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/playlists/170/items/902/move")
+                .query_param_missing("after");
+            then.status(200);
+        });
+        server
+            .move_in_playlist(&playlist, &items[1], None)
+            .await
+            .unwrap();
+        m.assert();
+        m.delete();
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/playlists/170/items/902/move")
+                .query_param("after", "901");
+            then.status(200);
+        });
+        server
+            .move_in_playlist(&playlist, &items[1], Some(&items[0]))
+            .await
+            .unwrap();
+        m.assert();
+        m.delete();
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(DELETE).path("/playlists/170/items/901");
+            then.status(200);
+        });
+        server
+            .remove_from_playlist(&playlist, &items[0])
+            .await
+            .unwrap();
+        m.assert();
+        m.delete();
+
+        let movies = movies(&server, &mock_server).await;
+
+        let m = mock_server.mock(|when, then| {
+            when.method(PUT).path("/playlists/170/items").query_param(
+                "uri",
+                "server://machine_id3/com.plexapp.plugins.library/library/metadata/108",
+            );
+            then.status(200);
+        });
+        server
+            .add_to_playlist(&playlist, &movies[2..3])
+            .await
+            .unwrap();
+        m.assert();
+
+        let result = server.add_to_playlist::<Video, Movie>(&playlist, &[]).await;
+        assert!(
+            matches!(result, Err(Error::NoItemsProvided)),
+            "unexpected result: {result:?}"
+        );
+        m.assert_calls(1);
+
+        // Only items retrieved from the playlist can be removed.
+        let result = server
+            .remove_from_playlist(&playlist, &Video::from(movies[2].clone()))
+            .await;
+        assert!(
+            matches!(result, Err(Error::PlaylistItemIdNotAvailable)),
+            "unexpected result: {result:?}"
+        );
+    }
+}
+
+mod online {
+    use super::fixtures::online::server::*;
+    use plex_api::{
+        library::{Library, MetadataItem, Playlist, Video},
+        Server,
+    };
+
+    #[plex_api_test_helper::online_test_non_shared_server]
+    async fn create_and_edit(#[future] server: Server) {
+        let library = server
+            .libraries()
+            .into_iter()
+            .find_map(|l| match l {
+                Library::Movie(library) => Some(library),
+                _ => None,
+            })
+            .expect("no movie library found");
+        let movies = library.movies().await.unwrap();
+
+        let playlist: Playlist<Video> = server
+            .create_playlist("Test Playlist", &movies[..2])
+            .await
+            .unwrap();
+        assert_eq!(playlist.title(), "Test Playlist");
+
+        let items = playlist.children().await.unwrap();
+        assert_eq!(
+            items.iter().map(|i| i.rating_key()).collect::<Vec<_>>(),
+            movies[..2]
+                .iter()
+                .map(|m| m.rating_key())
+                .collect::<Vec<_>>()
+        );
+
+        server
+            .move_in_playlist(&playlist, &items[1], None)
+            .await
+            .unwrap();
+        server
+            .remove_from_playlist(&playlist, &items[0])
+            .await
+            .unwrap();
+        assert_eq!(playlist.children().await.unwrap().len(), 1);
+
+        playlist.delete().await.unwrap();
+    }
+}
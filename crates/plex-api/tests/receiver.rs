@@ -0,0 +1,250 @@
+mod fixtures;
+
+mod offline {
+    use super::fixtures::offline::{server::*, Mocked};
+    use futures::StreamExt;
+    use httpmock::Method::GET;
+    use plex_api::{
+        library::MetadataItem,
+        media_container::player::{
+            DeviceClass, PlaybackState, Player as PlayerResources, ProtocolCapability, Timeline,
+        },
+        NavigationCommand, PlayMediaRequest, PlaybackType, Player, Receiver, ReceiverHandler,
+        Server,
+    };
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+        sync::{Arc, Mutex},
+    };
+
+    #[derive(Debug, Default)]
+    struct Playback {
+        commands: Vec<String>,
+        state: Option<PlaybackState>,
+        media: Option<PlayMediaRequest>,
+        offset: u64,
+    }
+
+    struct TestHandler(Arc<Mutex<Playback>>);
+
+    impl ReceiverHandler for TestHandler {
+        fn play_media(&mut self, request: PlayMediaRequest) {
+            let mut playback = self.0.lock().unwrap();
+            playback.commands.push("playMedia".to_owned());
+            playback.state = Some(PlaybackState::Playing);
+            playback.offset = request.offset;
+            playback.media = Some(request);
+        }
+
+        fn play(&mut self, _playback_type: PlaybackType) {
+            let mut playback = self.0.lock().unwrap();
+            playback.commands.push("play".to_owned());
+            playback.state = Some(PlaybackState::Playing);
+        }
+
+        fn pause(&mut self, _playback_type: PlaybackType) {
+            let mut playback = self.0.lock().unwrap();
+            playback.commands.push("pause".to_owned());
+            playback.state = Some(PlaybackState::Paused);
+        }
+
+        fn stop(&mut self, _playback_type: PlaybackType) {
+            let mut playback = self.0.lock().unwrap();
+            playback.commands.push("stop".to_owned());
+            playback.state = Some(PlaybackState::Stopped);
+        }
+
+        fn seek_to(&mut self, _playback_type: PlaybackType, offset: u64) {
+            let mut playback = self.0.lock().unwrap();
+            playback.commands.push(format!("seekTo {offset}"));
+            playback.offset = offset;
+        }
+
+        fn set_streams(
+            &mut self,
+            _playback_type: PlaybackType,
+            audio_stream_id: Option<u64>,
+            subtitle_stream_id: Option<u64>,
+        ) {
+            self.0.lock().unwrap().commands.push(format!(
+                "setStreams {audio_stream_id:?} {subtitle_stream_id:?}"
+            ));
+        }
+
+        fn timelines(&self) -> Vec<Timeline> {
+            let playback = self.0.lock().unwrap();
+            let mut video = Timeline::new(
+                PlaybackType::Video,
+                playback.state.unwrap_or(PlaybackState::Stopped),
+            );
+            if let Some(ref media) = playback.media {
+                video.key = Some(media.key.clone());
+                video.time = Some(playback.offset);
+            }
+
+            vec![
+                Timeline::new(PlaybackType::Music, PlaybackState::Stopped),
+                video,
+                Timeline::new(PlaybackType::Photo, PlaybackState::Stopped),
+            ]
+        }
+    }
+
+    fn resources() -> PlayerResources {
+        PlayerResources {
+            machine_identifier: "receiver_id".to_owned(),
+            product: "Kiosk".to_owned(),
+            protocol: "plex".to_owned(),
+            protocol_version: 1,
+            device_class: DeviceClass::Pc,
+            platform: "Linux".to_owned(),
+            platform_version: "6.1".to_owned(),
+            title: "Lobby Screen".to_owned(),
+            protocol_capabilities: vec![
+                ProtocolCapability::Playback,
+                ProtocolCapability::Timeline,
+                ProtocolCapability::PlayQueues,
+            ],
+        }
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn receiver(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let playback = Arc::new(Mutex::new(Playback::default()));
+        let receiver = Receiver::start(
+            TcpListener::bind("127.0.0.1:0").unwrap(),
+            resources(),
+            TestHandler(playback.clone()),
+        )
+        .unwrap();
+
+        let player = Player::new(
+            format!("http://{}", receiver.local_addr()),
+            server.client().clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(player.machine_identifier(), "receiver_id");
+        assert_eq!(player.title(), "Lobby Screen");
+        assert!(player.supports(ProtocolCapability::Timeline));
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/metadata/182");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/timeline/metadata_182_1.json");
+        });
+        let item = server.item_by_id("182").await.unwrap();
+        m.assert();
+        m.delete();
+
+        player
+            .play_media(&server, &item, None, 70000)
+            .await
+            .unwrap();
+        {
+            let playback = playback.lock().unwrap();
+            let media = playback.media.as_ref().unwrap();
+            assert_eq!(media.key, item.metadata().key);
+            assert_eq!(media.offset, 70000);
+            assert_eq!(media.playback_type, PlaybackType::Video);
+            assert_eq!(media.machine_identifier.as_deref(), Some("machine_id3"));
+            assert_eq!(media.token.as_deref(), Some("fixture_auth_token"));
+            assert_eq!(
+                media.server_url().as_deref(),
+                Some(server.client().api_url.to_string().trim_end_matches('/'))
+            );
+        }
+
+        player.pause(PlaybackType::Video).await.unwrap();
+        player.seek_to(PlaybackType::Video, 75000).await.unwrap();
+        player
+            .set_streams(PlaybackType::Video, Some(12), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            playback.lock().unwrap().commands,
+            vec![
+                "playMedia",
+                "pause",
+                "seekTo 75000",
+                "setStreams Some(12) None"
+            ]
+        );
+
+        // The first poll returns the current state straight away.
+        let timelines = player.timeline().await.unwrap();
+        assert_eq!(timelines.command_id, Some(5));
+        let video = timelines.timeline(PlaybackType::Video).unwrap();
+        assert_eq!(video.state, PlaybackState::Paused);
+        assert_eq!(video.time, Some(75000));
+        assert_eq!(video.key, item.metadata().key.clone().into());
+
+        // Commands for capabilities the receiver doesn't advertise are refused.
+        assert!(player.navigate(NavigationCommand::Home).await.is_err());
+
+        let mut subscription = player
+            .subscribe(TcpListener::bind("127.0.0.1:0").unwrap())
+            .await
+            .unwrap();
+
+        // Subscribers get the current timelines straight away and then every
+        // time they change.
+        let timelines = subscription.next().await.unwrap().unwrap();
+        let video = timelines.timeline(PlaybackType::Video).unwrap();
+        assert_eq!(video.state, PlaybackState::Paused);
+
+        player.play(PlaybackType::Video).await.unwrap();
+        let timelines = subscription.next().await.unwrap().unwrap();
+        assert_eq!(timelines.command_id, Some(7));
+        let video = timelines.timeline(PlaybackType::Video).unwrap();
+        assert_eq!(video.state, PlaybackState::Playing);
+
+        player.stop(PlaybackType::Video).await.unwrap();
+        let timelines = subscription.next().await.unwrap().unwrap();
+        let video = timelines.timeline(PlaybackType::Video).unwrap();
+        assert_eq!(video.state, PlaybackState::Stopped);
+
+        subscription.unsubscribe().await.unwrap();
+    }
+
+    fn send_raw(receiver: &Receiver, request: &str) -> String {
+        let mut stream = TcpStream::connect(receiver.local_addr()).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn receiver_rejects_bad_requests() {
+        let receiver = Receiver::start(
+            TcpListener::bind("127.0.0.1:0").unwrap(),
+            resources(),
+            TestHandler(Default::default()),
+        )
+        .unwrap();
+
+        let response = send_raw(
+            &receiver,
+            "POST /player/playback/play HTTP/1.1\r\nContent-Length: 1000000000\r\n\r\n",
+        );
+        assert!(response.starts_with("HTTP/1.1 413 "), "{response}");
+
+        let headers: String = (0..100).map(|i| format!("X-Header-{i}: 1\r\n")).collect();
+        let response = send_raw(
+            &receiver,
+            &format!("GET /resources HTTP/1.1\r\n{headers}\r\n"),
+        );
+        assert!(response.starts_with("HTTP/1.1 431 "), "{response}");
+
+        let response = send_raw(
+            &receiver,
+            "GET /player/timeline/subscribe?port=1234&protocol=gopher HTTP/1.1\r\nX-Plex-Client-Identifier: controller\r\n\r\n",
+        );
+        assert!(response.starts_with("HTTP/1.1 400 "), "{response}");
+    }
+}
@@ -11,9 +11,10 @@ mod offline {
     use crate::map;
 
     use super::fixtures::offline::{client::*, server::*, Mocked};
+    use futures::TryStreamExt;
     use httpmock::Method::GET;
     use plex_api::{
-        library::{Collection, Item, Library, MetadataItem, Movie, Playlist, Video},
+        library::{Collection, Item, Library, MetadataItem, Movie, PagingOptions, Playlist, Video},
         media_container::server::library::SearchType,
         url::{MYPLEX_USER_INFO_PATH, SERVER_MEDIA_PROVIDERS},
         HttpClient, Server,
@@ -106,6 +107,60 @@ mod offline {
         );
     }
 
+    #[plex_api_test_helper::offline_test]
+    async fn movie_library_stream(#[future] server_anonymous: Mocked<Server>) {
+        let (server, mock_server) = server_anonymous.split();
+
+        let libraries = server.libraries();
+        let library = if let Library::Movie(lib) = &libraries[0] {
+            lib
+        } else {
+            panic!("Unexpected library: {:?}", libraries[0]);
+        };
+
+        let mut m_page1 = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/library/sections/1/all")
+                .query_param("type", "1")
+                .header("X-Plex-Container-Start", "0")
+                .header("X-Plex-Container-Size", "2");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/movie_library_page1.json");
+        });
+        let mut m_page2 = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/library/sections/1/all")
+                .query_param("type", "1")
+                .header("X-Plex-Container-Start", "2")
+                .header("X-Plex-Container-Size", "2");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/media/movie_library_page2.json");
+        });
+
+        let stream = library
+            .movies_stream(PagingOptions {
+                page_size: 2,
+                prefetch: 1,
+            })
+            .await
+            .unwrap();
+        assert_eq!(stream.total_size(), 4);
+        m_page1.assert();
+        m_page2.assert_calls(0);
+
+        let movies: Vec<Movie> = stream.try_collect().await.unwrap();
+        m_page2.assert();
+        m_page1.delete();
+        m_page2.delete();
+
+        assert_eq!(
+            map(&movies, |e| e.rating_key().to_owned()),
+            vec!["55", "56", "108", "57"]
+        );
+    }
+
     #[plex_api_test_helper::offline_test]
     async fn tv_library(#[future] server_anonymous: Mocked<Server>) {
         let (server, mock_server) = server_anonymous.split();
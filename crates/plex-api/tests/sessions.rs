@@ -0,0 +1,112 @@
+mod fixtures;
+
+mod offline {
+    use super::fixtures::offline::{server::*, Mocked};
+    use httpmock::Method::GET;
+    use plex_api::{
+        library::{Item, MetadataItem},
+        media_container::server::session::{PlaybackState, SessionLocation},
+        url::{SERVER_SESSIONS, SERVER_SESSIONS_TERMINATE},
+        Error, Server,
+    };
+
+    #[plex_api_test_helper::offline_test]
+    async fn list_sessions(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_SESSIONS);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/sessions.json");
+        });
+
+        let sessions = server.sessions().await.unwrap();
+        m.assert();
+        assert_eq!(sessions.len(), 2);
+
+        let movie = &sessions[0];
+        assert!(matches!(movie.item(), Item::Movie(_)));
+        assert_eq!(movie.item().title(), "Sintel");
+        assert_eq!(movie.id(), Some("nxyvkp7lfq3tdw6c0oei2s1h"));
+        assert_eq!(movie.user().unwrap().title, "admin");
+        assert_eq!(movie.state(), Some(PlaybackState::Playing));
+        assert_eq!(movie.view_offset(), Some(253000));
+        assert_eq!(movie.bandwidth(), Some(5120));
+        assert_eq!(movie.location(), Some(SessionLocation::Wan));
+        let player = movie.player().unwrap();
+        assert_eq!(player.product.as_deref(), Some("Plex Web"));
+        assert!(!player.local);
+        assert!(movie.transcode_session().is_some());
+
+        let track = &sessions[1];
+        assert!(matches!(track.item(), Item::Track(_)));
+        assert_eq!(track.user().unwrap().id, 8342987);
+        assert_eq!(track.state(), Some(PlaybackState::Paused));
+        assert!(track.player().unwrap().local);
+        assert!(track.transcode_session().is_none());
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn terminate_session(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_SESSIONS);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/server/sessions.json");
+        });
+
+        let sessions = server.sessions().await.unwrap();
+        m.assert();
+        m.delete();
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path(SERVER_SESSIONS_TERMINATE)
+                .query_param("sessionId", "nxyvkp7lfq3tdw6c0oei2s1h")
+                .query_param("reason", "Remote streaming is not allowed");
+            then.status(200);
+        });
+
+        sessions[0]
+            .terminate("Remote streaming is not allowed")
+            .await
+            .unwrap();
+        m.assert();
+        m.delete();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET).path(SERVER_SESSIONS_TERMINATE);
+            then.status(401)
+                .header("content-type", "text/html")
+                .body("<html><head><title>Unauthorized</title></head><body><h1>401 Unauthorized</h1></body></html>");
+        });
+
+        let result = sessions[1].terminate("Bye").await;
+        m.assert();
+        assert!(
+            matches!(
+                result,
+                Err(Error::UnexpectedApiResponse {
+                    status_code: 401,
+                    ..
+                })
+            ),
+            "unexpected result: {result:?}"
+        );
+    }
+}
+
+mod online {
+    use super::fixtures::online::server::*;
+    use plex_api::Server;
+
+    #[plex_api_test_helper::online_test]
+    async fn list_sessions(#[future] server: Server) {
+        // Nothing is played on the test server.
+        let sessions = server.sessions().await.unwrap();
+        assert!(sessions.is_empty());
+    }
+}
@@ -0,0 +1,132 @@
+mod fixtures;
+
+mod offline {
+    use super::fixtures::offline::{server::*, Mocked};
+    use httpmock::{
+        Method::{GET, PUT},
+        MockServer,
+    };
+    use plex_api::{
+        library::{MediaItem, MediaItemWithTranscoding, Movie, StreamSelectionScope},
+        media_container::server::library::Protocol,
+        transcode::{SubtitleSelection, VideoTranscodeOptions},
+        Server,
+    };
+
+    async fn movie(server: &Server, mock_server: &MockServer) -> Movie {
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/metadata/159637");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/transcode/metadata_159637.json");
+        });
+
+        let item = server
+            .item_by_id("159637")
+            .await
+            .unwrap()
+            .try_into()
+            .unwrap();
+        m.assert();
+        m.delete();
+        item
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn select_audio_stream(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let movie = movie(&server, &mock_server).await;
+        let media = &movie.media()[0];
+        let part = &media.parts()[0];
+
+        let audio_streams = part.audio_streams();
+        assert_eq!(audio_streams.len(), 1);
+        assert_eq!(audio_streams[0].selected, Some(true));
+
+        let m = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/library/parts/320497")
+                .query_param("audioStreamID", "566076")
+                .query_param("allParts", "1")
+                .query_param_missing("allUsers");
+            then.status(200);
+        });
+
+        part.select_audio_stream(audio_streams[0], StreamSelectionScope::CurrentUser)
+            .await
+            .unwrap();
+        m.assert();
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn select_subtitle_stream(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let movie = movie(&server, &mock_server).await;
+        let media = &movie.media()[0];
+        let part = &media.parts()[0];
+
+        let subtitle_streams = part.subtitle_streams();
+        assert_eq!(subtitle_streams.len(), 2);
+        assert_eq!(subtitle_streams[1].hearing_impaired, Some(true));
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/library/parts/320497")
+                .query_param("subtitleStreamID", "566078")
+                .query_param("allParts", "1")
+                .query_param("allUsers", "1");
+            then.status(200);
+        });
+
+        part.select_subtitle_stream(Some(subtitle_streams[1]), StreamSelectionScope::AllUsers)
+            .await
+            .unwrap();
+        m.assert();
+        m.delete();
+
+        let m = mock_server.mock(|when, then| {
+            when.method(PUT)
+                .path("/library/parts/320497")
+                .query_param("subtitleStreamID", "0")
+                .query_param("allParts", "1");
+            then.status(200);
+        });
+
+        part.select_subtitle_stream(None, StreamSelectionScope::CurrentUser)
+            .await
+            .unwrap();
+        m.assert();
+    }
+
+    #[plex_api_test_helper::offline_test]
+    async fn streaming_session_streams(#[future] server_authenticated: Mocked<Server>) {
+        let (server, mock_server) = server_authenticated.split();
+        let movie = movie(&server, &mock_server).await;
+        let media = &movie.media()[0];
+        let part = &media.parts()[0];
+
+        let m = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/video/:/transcode/universal/decision")
+                .query_param("path", "/library/metadata/159637")
+                .query_param("protocol", "dash")
+                .query_param("audioStreamID", "566076")
+                .query_param("subtitleStreamID", "0");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/transcode/video_dash_h264_mp3.json");
+        });
+
+        part.create_streaming_session(
+            Protocol::Dash,
+            VideoTranscodeOptions {
+                audio_stream: Some(part.audio_streams()[0].id.clone()),
+                subtitle_stream: Some(SubtitleSelection::None),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        m.assert();
+    }
+}
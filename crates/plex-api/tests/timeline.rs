@@ -6,8 +6,10 @@ mod offline {
     use httpmock::Method::GET;
     use plex_api::{
         library::{MetadataItem, Movie},
+        playback::{PlaybackReporter, PlaybackState},
         Server,
     };
+    use std::time::Duration;
 
     #[plex_api_test_helper::offline_test]
     async fn timeline(#[future] server_anonymous: Mocked<Server>) {
@@ -111,6 +113,94 @@ mod offline {
         assert_eq!(metadata.view_count, None);
         assert_eq!(metadata.view_offset, None);
     }
+
+    #[plex_api_test_helper::offline_test]
+    async fn playback_reporter(#[future] server_anonymous: Mocked<Server>) {
+        let (server, mock_server) = server_anonymous.split();
+
+        let mut m = mock_server.mock(|when, then| {
+            when.method(GET).path("/library/metadata/182");
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/timeline/metadata_182_1.json");
+        });
+
+        let movie: Movie = server.item_by_id("182").await.unwrap().try_into().unwrap();
+        m.assert();
+        m.delete();
+
+        let mut reporter = PlaybackReporter::new(movie);
+        let session_id = reporter.session_id().to_owned();
+
+        let mut playing = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/:/timeline")
+                .query_param("key", "/library/metadata/182")
+                .query_param("ratingKey", "182")
+                .query_param("state", "playing")
+                .query_param("duration", "81030")
+                .header("X-Plex-Session-Identifier", &session_id);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/timeline/timeline.json");
+        });
+        let mut paused = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/:/timeline")
+                .query_param("state", "paused")
+                .query_param("time", "12000")
+                .header("X-Plex-Session-Identifier", &session_id);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/timeline/timeline.json");
+        });
+
+        assert!(reporter.playing(10000).await.unwrap());
+        // Reports of the same state are throttled.
+        assert!(!reporter.playing(12000).await.unwrap());
+        playing.assert_calls(1);
+        // Changes of state are always reported.
+        assert!(reporter.paused(12000).await.unwrap());
+        paused.assert();
+        assert_eq!(reporter.state(), PlaybackState::Paused);
+        assert!(!reporter.is_scrobbled());
+        paused.delete();
+
+        let mut scrobble = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/:/scrobble")
+                .query_param("key", "182")
+                .query_param("identifier", "com.plexapp.plugins.library");
+            then.status(200);
+        });
+
+        // Playing past 90% of the duration marks the item as watched, once.
+        reporter.set_report_interval(Duration::ZERO);
+        assert!(reporter.playing(75000).await.unwrap());
+        assert!(reporter.playing(76000).await.unwrap());
+        playing.assert_calls(3);
+        scrobble.assert_calls(1);
+        assert!(reporter.is_scrobbled());
+        playing.delete();
+        scrobble.delete();
+
+        let stopped = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/:/timeline")
+                .query_param("state", "stopped")
+                .query_param("time", "76000")
+                .header("X-Plex-Session-Identifier", &session_id);
+            then.status(200)
+                .header("content-type", "text/json")
+                .body_from_file("tests/mocks/timeline/timeline.json");
+        });
+
+        reporter.stopped(76000).await.unwrap();
+        reporter.stopped(76000).await.unwrap();
+        stopped.assert_calls(1);
+        // Resuming playback starts a new session.
+        assert_ne!(reporter.session_id(), session_id);
+    }
 }
 
 mod online {